@@ -0,0 +1,1012 @@
+//! Show rows of data as a table with typed columns.
+//!
+//! The table is laid out by a [`Grid`](crate::grid::Grid), with a header row
+//! that sorts, resizes and reorders the columns. Cells of editable columns
+//! turn into an editor when they are double-clicked.
+use std::{cmp::Ordering, collections::HashMap};
+
+use iced::{
+    widget::{checkbox, text, text_input},
+    Element, Length, Renderer,
+};
+use iced_lazy::Component;
+use iced_native::widget::{
+    operation::{self, Operation},
+    tree::{self, Tree},
+    Widget,
+};
+use iced_native::{
+    event, keyboard,
+    layout::{Limits, Node},
+    mouse, renderer, Clipboard, Color, Event as NativeEvent, Layout, Point, Rectangle,
+    Renderer as _, Shell,
+};
+
+use crate::grid::{Cell, Grid, Reorder, Track};
+
+/// The narrowest a column can be resized to.
+const MIN_COLUMN_WIDTH: f32 = 24.0;
+
+/// The width of the area at the right edge of a header that resizes the
+/// column.
+const RESIZE_HANDLE_WIDTH: f32 = 6.0;
+
+/// How far the mouse has to move before a header is dragged.
+const DRAG_THRESHOLD: f32 = 4.0;
+
+/// The width of the line showing where a dragged column is dropped.
+const DROP_INDICATOR_WIDTH: f32 = 2.0;
+
+/// Compares two rows of type `T`.
+type Compare<'a, T> = dyn Fn(&T, &T) -> Ordering + 'a;
+
+/// Decides whether a row of type `T` is shown.
+type Filter<'a, T> = dyn Fn(&T) -> bool + 'a;
+
+/// Reads the [`Value`](Value) of a row of type `T`.
+type Edit<'a, T> = dyn Fn(&T) -> Value + 'a;
+
+/// A column of a [`DataGrid`](DataGrid) showing a value of every row of type
+/// `T`.
+#[allow(missing_debug_implementations)]
+pub struct Column<'a, T, Message> {
+    /// The title shown in the header.
+    header: String,
+    /// How wide the column is, until it is resized.
+    width: Track,
+    /// Builds the cell of a row from its index and value.
+    view: Box<dyn Fn(usize, &'a T) -> Element<'a, Message> + 'a>,
+    /// Compares two rows to sort them by this column, if it can be sorted.
+    compare: Option<Box<Compare<'a, T>>>,
+    /// Reads the value of a row to edit, if the column can be edited.
+    edit: Option<Box<Edit<'a, T>>>,
+}
+
+impl<'a, T, Message> Column<'a, T, Message> {
+    /// Creates a new [`Column`](Column) with the given `header`, showing the
+    /// element built by `view` for every row.
+    pub fn new<F>(header: impl Into<String>, view: F) -> Self
+    where
+        F: Fn(usize, &'a T) -> Element<'a, Message> + 'a,
+    {
+        Self {
+            header: header.into(),
+            width: Track::Shrink,
+            view: Box::new(view),
+            compare: None,
+            edit: None,
+        }
+    }
+
+    /// Sets the [`Track`](Track) sizing the [`Column`](Column).
+    pub fn width(self, width: Track) -> Self {
+        Self { width, ..self }
+    }
+
+    /// Makes the [`Column`](Column) sortable by comparing rows with
+    /// `compare`.
+    pub fn sort_by<F>(self, compare: F) -> Self
+    where
+        F: Fn(&T, &T) -> Ordering + 'a,
+    {
+        Self {
+            compare: Some(Box::new(compare)),
+            ..self
+        }
+    }
+
+    /// Makes the [`Column`](Column) sortable by the `key` of the rows.
+    pub fn sort_by_key<K, F>(self, key: F) -> Self
+    where
+        K: Ord,
+        F: Fn(&T) -> K + 'a,
+    {
+        self.sort_by(move |a, b| key(a).cmp(&key(b)))
+    }
+
+    /// Makes the cells of the [`Column`](Column) editable, starting from the
+    /// [`Value`](Value) `get` reads from their row.
+    ///
+    /// The editor matches the type of the value: a text input for text, a
+    /// checkbox for booleans and a number field for integers.
+    pub fn editable<F>(self, get: F) -> Self
+    where
+        F: Fn(&T) -> Value + 'a,
+    {
+        Self {
+            edit: Some(Box::new(get)),
+            ..self
+        }
+    }
+}
+
+/// The value of a cell of an editable [`Column`](Column).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    Text(String),
+    Bool(bool),
+    Integer(i64),
+}
+
+/// A cell of a [`DataGrid`](DataGrid) that was edited.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CellEdited {
+    /// The index of the row in the rows of the [`DataGrid`](DataGrid).
+    pub row: usize,
+    /// The index of the column, in the order the columns were given.
+    pub column: usize,
+    /// The new value of the cell.
+    pub value: Value,
+}
+
+/// The direction rows are sorted in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+/// A table showing `rows` in typed [`Column`](Column)s.
+///
+/// Clicking a header sorts the rows by its column, dragging the right edge of
+/// a header resizes the column and dragging a header moves the column.
+#[allow(missing_debug_implementations)]
+pub struct DataGrid<'a, T, Message> {
+    /// The columns of the table, in their initial order.
+    columns: Vec<Column<'a, T, Message>>,
+    /// The rows of the table.
+    rows: &'a [T],
+    /// Decides which rows are shown, if not all of them.
+    filter: Option<Box<Filter<'a, T>>>,
+    /// The space between two cells.
+    spacing: u16,
+    /// The message produced when a cell is edited.
+    on_cell_edited: Option<Box<dyn Fn(CellEdited) -> Message + 'a>>,
+}
+
+impl<'a, T, Message> DataGrid<'a, T, Message> {
+    /// Creates a new [`DataGrid`](DataGrid) showing the `rows` in the given
+    /// `columns`.
+    pub fn new(columns: Vec<Column<'a, T, Message>>, rows: &'a [T]) -> Self {
+        Self {
+            columns,
+            rows,
+            filter: None,
+            spacing: 0,
+            on_cell_edited: None,
+        }
+    }
+
+    /// Only shows the rows for which `filter` returns `true`.
+    pub fn filter<F>(self, filter: F) -> Self
+    where
+        F: Fn(&T) -> bool + 'a,
+    {
+        Self {
+            filter: Some(Box::new(filter)),
+            ..self
+        }
+    }
+
+    /// Sets the space between two cells.
+    pub fn spacing(self, spacing: u16) -> Self {
+        Self { spacing, ..self }
+    }
+
+    /// Sets the message produced when the edit of a cell is committed.
+    pub fn on_cell_edited<F>(self, on_cell_edited: F) -> Self
+    where
+        F: Fn(CellEdited) -> Message + 'a,
+    {
+        Self {
+            on_cell_edited: Some(Box::new(on_cell_edited)),
+            ..self
+        }
+    }
+
+    /// Returns the indices of the rows shown, in the order they are shown.
+    fn visible_rows(&self, state: &State) -> Vec<usize> {
+        let compare = state.sort.and_then(|(column, sort_order)| {
+            let compare = self.columns.get(column)?.compare.as_deref()?;
+            Some((compare, sort_order))
+        });
+
+        sorted_rows(self.rows, self.filter.as_deref(), compare)
+    }
+
+    /// Returns the row and column shown in the `cell` of the
+    /// [`Grid`](Grid), if it is not a header.
+    fn cell_at(&self, state: &State, cell: usize) -> Option<(usize, usize)> {
+        let order = column_order(&state.order, self.columns.len());
+        let (position, column) = cell_position(cell, order.len())?;
+
+        Some((*self.visible_rows(state).get(position)?, order[column]))
+    }
+}
+
+/// Returns the position in the shown rows and in the shown columns of the
+/// `cell` of the [`Grid`](Grid) of a table with `columns` columns, if it is
+/// not a header.
+fn cell_position(cell: usize, columns: usize) -> Option<(usize, usize)> {
+    if columns == 0 {
+        return None;
+    }
+    let body = cell.checked_sub(columns)?;
+
+    Some((body / columns, body % columns))
+}
+
+/// The local state of a [`DataGrid`](DataGrid).
+#[derive(Debug, Default)]
+pub struct State {
+    /// The column the rows are sorted by, if any.
+    sort: Option<(usize, SortOrder)>,
+    /// The width of the columns that have been resized.
+    widths: HashMap<usize, u16>,
+    /// The order the columns are shown in, if they have been moved.
+    order: Vec<usize>,
+    /// The column whose header is being dragged, if any.
+    dragged: Option<usize>,
+    /// The cell being edited, if any.
+    editing: Option<Editing>,
+}
+
+/// A cell of a [`DataGrid`](DataGrid) being edited.
+#[derive(Debug, Clone)]
+struct Editing {
+    /// The index of the row.
+    row: usize,
+    /// The index of the column.
+    column: usize,
+    /// The value typed so far.
+    draft: Draft,
+}
+
+/// The value of a cell being edited.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Draft {
+    Text(String),
+    Bool(bool),
+    /// The digits of an integer, which may not be a whole number yet while
+    /// it is typed.
+    Integer(String),
+}
+
+impl Draft {
+    /// Replaces the typed text with `input`, unless an integer is expected
+    /// and `input` cannot start one.
+    fn input(&mut self, input: String) {
+        match self {
+            Draft::Text(text) => *text = input,
+            Draft::Integer(digits) if is_integer_prefix(&input) => *digits = input,
+            Draft::Integer(_) | Draft::Bool(_) => {}
+        }
+    }
+
+    /// Returns the [`Value`](Value) of the draft, if it is complete.
+    fn value(&self) -> Option<Value> {
+        match self {
+            Draft::Text(text) => Some(Value::Text(text.clone())),
+            Draft::Bool(is_checked) => Some(Value::Bool(*is_checked)),
+            Draft::Integer(digits) => digits.parse().ok().map(Value::Integer),
+        }
+    }
+}
+
+impl From<Value> for Draft {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Text(text) => Draft::Text(text),
+            Value::Bool(is_checked) => Draft::Bool(is_checked),
+            Value::Integer(integer) => Draft::Integer(integer.to_string()),
+        }
+    }
+}
+
+/// Returns whether `input` is an integer, or the start of one.
+fn is_integer_prefix(input: &str) -> bool {
+    let digits = input.strip_prefix('-').unwrap_or(input);
+
+    digits.chars().all(|c| c.is_ascii_digit())
+}
+
+/// The interactions with a [`DataGrid`](DataGrid).
+#[derive(Debug, Clone)]
+pub enum Event<Message> {
+    Sort(usize),
+    Resize(usize, f32),
+    DragStarted(usize),
+    Dropped {
+        from: usize,
+        to: usize,
+        after: bool,
+    },
+    DragEnded,
+    Cell(Message),
+    /// A cell was double-clicked or Enter was pressed on it.
+    Activate(usize),
+    EditInput(String),
+    EditToggled(bool),
+    CommitEdit,
+    CancelEdit,
+}
+
+impl<'a, T, Message> Component<Message, Renderer> for DataGrid<'a, T, Message>
+where
+    Message: Clone + 'static,
+{
+    type State = State;
+    type Event = Event<Message>;
+
+    fn update(&mut self, state: &mut Self::State, event: Self::Event) -> Option<Message> {
+        match event {
+            Event::Sort(column) => {
+                if self
+                    .columns
+                    .get(column)
+                    .is_some_and(|c| c.compare.is_some())
+                {
+                    state.sort = next_sort(state.sort, column);
+                }
+            }
+            Event::Resize(column, width) => {
+                state
+                    .widths
+                    .insert(column, width.max(MIN_COLUMN_WIDTH) as u16);
+            }
+            Event::DragStarted(column) => state.dragged = Some(column),
+            Event::Dropped { from, to, after } => {
+                let mut order = column_order(&state.order, self.columns.len());
+                move_column(&mut order, from, to, after);
+                state.order = order;
+            }
+            Event::DragEnded => state.dragged = None,
+            Event::Cell(message) => return Some(message),
+            Event::Activate(cell) => {
+                let (row, column) = self.cell_at(state, cell)?;
+
+                if let Some(edit) = &self.columns[column].edit {
+                    state.editing = Some(Editing {
+                        row,
+                        column,
+                        draft: edit(&self.rows[row]).into(),
+                    });
+                }
+            }
+            Event::EditInput(input) => {
+                if let Some(editing) = &mut state.editing {
+                    editing.draft.input(input);
+                }
+            }
+            Event::EditToggled(is_checked) => {
+                if let Some(editing) = &mut state.editing {
+                    editing.draft = Draft::Bool(is_checked);
+                }
+            }
+            Event::CommitEdit => {
+                let editing = state.editing.as_ref()?;
+                let value = editing.draft.value()?;
+                let edited = CellEdited {
+                    row: editing.row,
+                    column: editing.column,
+                    value,
+                };
+
+                state.editing = None;
+                return self
+                    .on_cell_edited
+                    .as_ref()
+                    .map(|on_cell_edited| on_cell_edited(edited));
+            }
+            Event::CancelEdit => state.editing = None,
+        }
+
+        None
+    }
+
+    fn view(&self, state: &Self::State) -> Element<'_, Self::Event> {
+        let rows: &'a [T] = self.rows;
+        let order = column_order(&state.order, self.columns.len());
+
+        let tracks = order
+            .iter()
+            .map(|&column| {
+                state
+                    .widths
+                    .get(&column)
+                    .map_or(self.columns[column].width, |&width| Track::Fixed(width))
+            })
+            .collect();
+        let mut grid = Grid::with_tracks(tracks, Vec::new())
+            .spacing(self.spacing)
+            .on_activate(Event::Activate);
+
+        for &column in &order {
+            let indicator = match state.sort {
+                Some((sorted, SortOrder::Ascending)) if sorted == column => " ▲",
+                Some((sorted, SortOrder::Descending)) if sorted == column => " ▼",
+                _ => "",
+            };
+            let header = Header {
+                column,
+                content: text(format!("{}{indicator}", self.columns[column].header)).into(),
+                dragged: state.dragged,
+            };
+
+            grid = grid.push_cell(header, Cell::new().fill());
+        }
+
+        for row in self.visible_rows(state) {
+            for &column in &order {
+                let cell = match &state.editing {
+                    Some(editing) if editing.row == row && editing.column == column => {
+                        editor(&editing.draft)
+                    }
+                    _ => (self.columns[column].view)(row, &rows[row]).map(Event::Cell),
+                };
+
+                grid = grid.push(cell);
+            }
+        }
+
+        grid.into()
+    }
+}
+
+impl<'a, T, Message> From<DataGrid<'a, T, Message>> for Element<'a, Message>
+where
+    Message: Clone + 'static,
+{
+    fn from(data_grid: DataGrid<'a, T, Message>) -> Self {
+        iced_lazy::component(data_grid)
+    }
+}
+
+/// Builds the editor of a cell holding the `draft`.
+fn editor<'a, Message>(draft: &Draft) -> Element<'a, Event<Message>>
+where
+    Message: Clone + 'static,
+{
+    let input = |value| {
+        let id = text_input::Id::unique();
+        let input = text_input("", value, Event::EditInput).id(id.clone());
+
+        (input.into(), Some(id))
+    };
+
+    let (content, focus) = match draft {
+        Draft::Text(text) => input(text.as_str()),
+        Draft::Integer(digits) => input(digits.as_str()),
+        Draft::Bool(is_checked) => (checkbox("", *is_checked, Event::EditToggled).into(), None),
+    };
+
+    Editor { content, focus }.into()
+}
+
+/// Returns the sort after clicking the header of `column`: ascending first,
+/// then descending, then unsorted.
+fn next_sort(current: Option<(usize, SortOrder)>, column: usize) -> Option<(usize, SortOrder)> {
+    match current {
+        Some((sorted, SortOrder::Ascending)) if sorted == column => {
+            Some((column, SortOrder::Descending))
+        }
+        Some((sorted, SortOrder::Descending)) if sorted == column => None,
+        _ => Some((column, SortOrder::Ascending)),
+    }
+}
+
+/// Returns the order of `len` columns, which is `order` unless it does not
+/// match the columns.
+fn column_order(order: &[usize], len: usize) -> Vec<usize> {
+    if order.len() == len {
+        order.to_vec()
+    } else {
+        (0..len).collect()
+    }
+}
+
+/// Moves the column `from` next to the column `to`, before it or `after` it.
+fn move_column(order: &mut Vec<usize>, from: usize, to: usize, after: bool) {
+    let (Some(from), Some(to)) = (
+        order.iter().position(|&column| column == from),
+        order.iter().position(|&column| column == to),
+    ) else {
+        return;
+    };
+
+    let slot = to + usize::from(after);
+    let to = if slot > from { slot - 1 } else { slot };
+    Reorder { from, to }.apply(order);
+}
+
+/// Returns the indices of the `rows` that pass the `filter`, sorted by
+/// `compare` in the given order if any. Rows that compare equal keep their
+/// order.
+fn sorted_rows<T>(
+    rows: &[T],
+    filter: Option<&Filter<'_, T>>,
+    compare: Option<(&Compare<'_, T>, SortOrder)>,
+) -> Vec<usize> {
+    let mut indices: Vec<_> = (0..rows.len())
+        .filter(|&index| filter.is_none_or(|filter| filter(&rows[index])))
+        .collect();
+
+    if let Some((compare, sort_order)) = compare {
+        indices.sort_by(|&a, &b| match sort_order {
+            SortOrder::Ascending => compare(&rows[a], &rows[b]),
+            SortOrder::Descending => compare(&rows[b], &rows[a]),
+        });
+    }
+
+    indices
+}
+
+/// The header of a column of a [`DataGrid`](DataGrid).
+struct Header<'a, Message> {
+    /// The index of the column.
+    column: usize,
+    /// The title of the column.
+    content: Element<'a, Event<Message>>,
+    /// The column whose header is being dragged, if any.
+    dragged: Option<usize>,
+}
+
+/// The local state of a [`Header`](Header).
+#[derive(Debug, Clone, Copy, Default)]
+enum Interaction {
+    #[default]
+    Idle,
+    /// The left button was pressed at `origin`.
+    Pressed { origin: Point },
+    /// The header is dragged to another column.
+    Dragging,
+    /// The right edge of the header is dragged, starting at `origin_x` when
+    /// the header, which fills its cell, was `width` wide.
+    Resizing { origin_x: f32, width: f32 },
+}
+
+impl<'a, Message> Header<'a, Message> {
+    /// Returns whether the `cursor_position` is over the resize handle.
+    fn is_over_handle(bounds: Rectangle, cursor_position: Point) -> bool {
+        bounds.contains(cursor_position)
+            && cursor_position.x >= bounds.x + bounds.width - RESIZE_HANDLE_WIDTH
+    }
+
+    /// Returns whether another column is dropped on this one.
+    fn is_drop_target(&self) -> bool {
+        self.dragged.is_some_and(|dragged| dragged != self.column)
+    }
+}
+
+impl<'a, Message> Widget<Event<Message>, Renderer> for Header<'a, Message> {
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<Interaction>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(Interaction::default())
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        vec![Tree::new(&self.content)]
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        tree.diff_children(std::slice::from_ref(&self.content));
+    }
+
+    fn width(&self) -> Length {
+        Length::Shrink
+    }
+
+    fn height(&self) -> Length {
+        Length::Shrink
+    }
+
+    fn layout(&self, renderer: &Renderer, limits: &Limits) -> Node {
+        let limits = limits.width(Length::Shrink).height(Length::Shrink);
+        let content = self.content.as_widget().layout(renderer, &limits);
+        let size = limits.resolve(content.size());
+
+        Node::with_children(size, vec![content])
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: NativeEvent,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        _renderer: &Renderer,
+        _clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Event<Message>>,
+    ) -> event::Status {
+        let bounds = layout.bounds();
+        let interaction = tree.state.downcast_mut::<Interaction>();
+
+        match event {
+            NativeEvent::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                if !bounds.contains(cursor_position) {
+                    return event::Status::Ignored;
+                }
+
+                *interaction = if Self::is_over_handle(bounds, cursor_position) {
+                    Interaction::Resizing {
+                        origin_x: cursor_position.x,
+                        width: bounds.width,
+                    }
+                } else {
+                    Interaction::Pressed {
+                        origin: cursor_position,
+                    }
+                };
+                event::Status::Captured
+            }
+            NativeEvent::Mouse(mouse::Event::CursorMoved { position }) => match *interaction {
+                Interaction::Pressed { origin } if origin.distance(position) > DRAG_THRESHOLD => {
+                    *interaction = Interaction::Dragging;
+                    shell.publish(Event::DragStarted(self.column));
+                    event::Status::Captured
+                }
+                Interaction::Resizing { origin_x, width } => {
+                    shell.publish(Event::Resize(self.column, width + position.x - origin_x));
+                    event::Status::Captured
+                }
+                _ => event::Status::Ignored,
+            },
+            NativeEvent::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                if let (Some(from), true) = (self.dragged, self.is_drop_target()) {
+                    if bounds.contains(cursor_position) {
+                        shell.publish(Event::Dropped {
+                            from,
+                            to: self.column,
+                            after: cursor_position.x > bounds.center_x(),
+                        });
+                    }
+                }
+
+                match std::mem::take(interaction) {
+                    Interaction::Idle => event::Status::Ignored,
+                    // only a click that ends on the header it started on sorts
+                    Interaction::Pressed { .. } => {
+                        if bounds.contains(cursor_position) {
+                            shell.publish(Event::Sort(self.column));
+                        }
+                        event::Status::Captured
+                    }
+                    Interaction::Dragging => {
+                        shell.publish(Event::DragEnded);
+                        event::Status::Captured
+                    }
+                    Interaction::Resizing { .. } => event::Status::Captured,
+                }
+            }
+            _ => event::Status::Ignored,
+        }
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        _viewport: &Rectangle,
+        _renderer: &Renderer,
+    ) -> mouse::Interaction {
+        let bounds = layout.bounds();
+
+        match tree.state.downcast_ref::<Interaction>() {
+            Interaction::Resizing { .. } => mouse::Interaction::ResizingHorizontally,
+            Interaction::Dragging => mouse::Interaction::Grabbing,
+            _ if Self::is_over_handle(bounds, cursor_position) => {
+                mouse::Interaction::ResizingHorizontally
+            }
+            _ if bounds.contains(cursor_position) => mouse::Interaction::Pointer,
+            _ => mouse::Interaction::default(),
+        }
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &iced::Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+    ) {
+        let bounds = layout.bounds();
+
+        if let Some(content) = layout.children().next() {
+            self.content.as_widget().draw(
+                &tree.children[0],
+                renderer,
+                theme,
+                style,
+                content,
+                cursor_position,
+                viewport,
+            );
+        }
+
+        if self.is_drop_target() && bounds.contains(cursor_position) {
+            let x = if cursor_position.x > bounds.center_x() {
+                bounds.x + bounds.width - DROP_INDICATOR_WIDTH
+            } else {
+                bounds.x
+            };
+
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds: Rectangle {
+                        x,
+                        width: DROP_INDICATOR_WIDTH,
+                        ..bounds
+                    },
+                    border_radius: Default::default(),
+                    border_width: 0.0,
+                    border_color: Color::TRANSPARENT,
+                },
+                style.text_color,
+            );
+        }
+    }
+}
+
+impl<'a, Message> From<Header<'a, Message>> for Element<'a, Event<Message>>
+where
+    Message: 'static,
+{
+    fn from(header: Header<'a, Message>) -> Self {
+        Element::new(header)
+    }
+}
+
+/// The editor of a cell of a [`DataGrid`](DataGrid), committing the edit on
+/// Enter and cancelling it on Escape.
+struct Editor<'a, Message> {
+    /// The input editing the value.
+    content: Element<'a, Event<Message>>,
+    /// The text input to focus once the editor is shown, if any.
+    focus: Option<text_input::Id>,
+}
+
+impl<'a, Message> Widget<Event<Message>, Renderer> for Editor<'a, Message> {
+    fn tag(&self) -> tree::Tag {
+        // Whether the text input has been focused.
+        tree::Tag::of::<bool>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(false)
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        vec![Tree::new(&self.content)]
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        tree.diff_children(std::slice::from_ref(&self.content));
+    }
+
+    fn width(&self) -> Length {
+        self.content.as_widget().width()
+    }
+
+    fn height(&self) -> Length {
+        self.content.as_widget().height()
+    }
+
+    fn layout(&self, renderer: &Renderer, limits: &Limits) -> Node {
+        let content = self.content.as_widget().layout(renderer, limits);
+
+        Node::with_children(content.size(), vec![content])
+    }
+
+    fn operate(
+        &self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        operation: &mut dyn Operation<Event<Message>>,
+    ) {
+        if let Some(content) = layout.children().next() {
+            self.content
+                .as_widget()
+                .operate(&mut tree.children[0], content, renderer, operation);
+        }
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: NativeEvent,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Event<Message>>,
+    ) -> event::Status {
+        let Some(content) = layout.children().next() else {
+            return event::Status::Ignored;
+        };
+
+        let is_focused = tree.state.downcast_mut::<bool>();
+        if !*is_focused {
+            *is_focused = true;
+            if let Some(id) = &self.focus {
+                let mut focus = operation::focusable::focus(id.clone().into());
+                self.content.as_widget().operate(
+                    &mut tree.children[0],
+                    content,
+                    renderer,
+                    &mut focus,
+                );
+            }
+        }
+
+        if let NativeEvent::Keyboard(keyboard::Event::KeyPressed { key_code, .. }) = event {
+            match key_code {
+                keyboard::KeyCode::Enter | keyboard::KeyCode::NumpadEnter => {
+                    shell.publish(Event::CommitEdit);
+                    return event::Status::Captured;
+                }
+                keyboard::KeyCode::Escape => {
+                    shell.publish(Event::CancelEdit);
+                    return event::Status::Captured;
+                }
+                _ => {}
+            }
+        }
+
+        self.content.as_widget_mut().on_event(
+            &mut tree.children[0],
+            event,
+            content,
+            cursor_position,
+            renderer,
+            clipboard,
+            shell,
+        )
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        layout
+            .children()
+            .next()
+            .map_or_else(mouse::Interaction::default, |content| {
+                self.content.as_widget().mouse_interaction(
+                    &tree.children[0],
+                    content,
+                    cursor_position,
+                    viewport,
+                    renderer,
+                )
+            })
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &iced::Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+    ) {
+        if let Some(content) = layout.children().next() {
+            self.content.as_widget().draw(
+                &tree.children[0],
+                renderer,
+                theme,
+                style,
+                content,
+                cursor_position,
+                viewport,
+            );
+        }
+    }
+}
+
+impl<'a, Message> From<Editor<'a, Message>> for Element<'a, Event<Message>>
+where
+    Message: 'static,
+{
+    fn from(editor: Editor<'a, Message>) -> Self {
+        Element::new(editor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_sort_cycles_through_orders() {
+        let ascending = next_sort(None, 1);
+        assert_eq!(ascending, Some((1, SortOrder::Ascending)));
+
+        let descending = next_sort(ascending, 1);
+        assert_eq!(descending, Some((1, SortOrder::Descending)));
+
+        assert_eq!(next_sort(descending, 1), None);
+        assert_eq!(next_sort(descending, 0), Some((0, SortOrder::Ascending)));
+    }
+
+    #[test]
+    fn sorted_rows_filter_and_sort() {
+        let rows = [3, 1, 4, 1, 5, 9, 2, 6];
+        let compare = |a: &i32, b: &i32| a.cmp(b);
+        let is_small = |row: &i32| *row < 5;
+
+        assert_eq!(
+            sorted_rows(&rows, None, None),
+            (0..rows.len()).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            sorted_rows(
+                &rows,
+                Some(&is_small),
+                Some((&compare, SortOrder::Ascending))
+            ),
+            vec![1, 3, 6, 0, 2]
+        );
+        assert_eq!(
+            sorted_rows(
+                &rows,
+                Some(&is_small),
+                Some((&compare, SortOrder::Descending))
+            ),
+            vec![2, 0, 6, 1, 3]
+        );
+    }
+
+    #[test]
+    fn move_column_before_or_after() {
+        let tests = vec![
+            (0, 2, false, vec![1, 0, 2, 3]),
+            (0, 2, true, vec![1, 2, 0, 3]),
+            (3, 0, false, vec![3, 0, 1, 2]),
+            (3, 1, true, vec![0, 1, 3, 2]),
+            (1, 1, true, vec![0, 1, 2, 3]),
+        ];
+
+        for (from, to, after, expected) in tests {
+            let mut order = vec![0, 1, 2, 3];
+            move_column(&mut order, from, to, after);
+            assert_eq!(order, expected, "{from} to {to}, after: {after}");
+        }
+    }
+
+    #[test]
+    fn cell_position_skips_headers() {
+        assert_eq!(cell_position(2, 3), None);
+        assert_eq!(cell_position(3, 3), Some((0, 0)));
+        assert_eq!(cell_position(8, 3), Some((1, 2)));
+        assert_eq!(cell_position(0, 0), None);
+    }
+
+    #[test]
+    fn integer_draft_only_accepts_digits() {
+        let mut draft = Draft::from(Value::Integer(12));
+        assert_eq!(draft.value(), Some(Value::Integer(12)));
+
+        draft.input("12a".into());
+        assert_eq!(draft, Draft::Integer("12".into()));
+
+        draft.input("-".into());
+        assert_eq!(draft.value(), None);
+
+        draft.input("-42".into());
+        assert_eq!(draft.value(), Some(Value::Integer(-42)));
+    }
+}
@@ -1,25 +1,449 @@
 //! Use a grid as an input element for creating grids.
 //!
 //! *This API requires the following crate features to be activated: `grid`*
+pub mod style;
+
+pub use style::StyleSheet;
+
+use iced_native::{
+    alignment::{Horizontal, Vertical},
+    event, keyboard,
+    layout::{Limits, Node},
+    mouse, renderer, window, Background, Clipboard, Color, Event, Layout, Length, Padding, Point,
+    Rectangle, Shell, Size, Vector,
+};
 use iced_native::{
-    event, layout::Node, mouse, Clipboard, Event, Layout, Length, Point, Rectangle, Shell, Size,
+    overlay,
+    widget::{
+        operation::Focusable,
+        tree::{self, Tree},
+        Operation,
+    },
+    Element, Widget,
 };
-use iced_native::{overlay, widget::Tree, Element, Widget};
+use std::{
+    cell::{OnceCell, RefCell},
+    cmp::Ordering,
+    collections::{BTreeSet, HashMap, HashSet},
+    ops::Range,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+/// Builds the element of a cell of a [`Grid`](Grid) from its index.
+type View<'a, Message, Renderer> = dyn Fn(usize) -> Element<'a, Message, Renderer> + 'a;
 
 #[allow(missing_debug_implementations)]
-pub struct Grid<'a, Message, Renderer> {
+pub struct Grid<'a, Message, Renderer>
+where
+    Renderer: iced_native::Renderer,
+    Renderer::Theme: StyleSheet,
+{
     /// The distribution [`Strategy`](Strategy) of the [`Grid`](Grid).
     strategy: Strategy,
     /// The elements in the [`Grid`](Grid).
     elements: Vec<Element<'a, Message, Renderer>>,
+    /// The [`Cell`](Cell) of each element, in the same order as `elements`.
+    cells: Vec<Cell>,
+    /// The horizontal space between two columns.
+    column_spacing: u16,
+    /// The vertical space between two rows.
+    row_spacing: u16,
+    /// The space around the cells.
+    padding: Padding,
+    /// The default horizontal alignment of an element in its cell.
+    align_x: Horizontal,
+    /// The default vertical alignment of an element in its cell.
+    align_y: Vertical,
+    /// The [`Direction`](Direction) rows are filled in.
+    direction: Direction,
+    /// Where the cells of a last row that is not full are placed.
+    last_row: Anchor,
+    /// The size of every cell, if they all share the same one.
+    ///
+    /// Cells can then be placed without laying them out, so only the ones in
+    /// the `viewport` are measured.
+    cell_size: Option<Size>,
+    /// The visible part of the [`Grid`](Grid), relative to its top left corner.
+    viewport: Option<Rectangle>,
+    /// Builds the elements of the [`Grid`](Grid) once it is known which are in
+    /// view.
+    view: Option<Box<View<'a, Message, Renderer>>>,
+    /// The cells the elements were built for.
+    built: Built,
+    /// The number of columns of cells of a fixed size the elements in view
+    /// were built for, so that they are laid out in as many.
+    built_columns: Option<usize>,
+    /// The number of cells in the [`Grid`](Grid), including the ones that
+    /// have not been built.
+    len: usize,
+    /// The message produced when the keyboard cursor moves to another cell.
+    on_focus_change: Option<Box<dyn Fn(usize) -> Message + 'a>>,
+    /// The message produced when cells are selected or deselected.
+    on_selection_changed: Option<Box<dyn Fn(Vec<usize>) -> Message + 'a>>,
+    /// The message produced when a cell is dragged to another position.
+    on_reorder: Option<Box<dyn Fn(Reorder) -> Message + 'a>>,
+    /// The message produced when a cell is double-clicked or Enter is
+    /// pressed on it.
+    on_activate: Option<Box<dyn Fn(usize) -> Message + 'a>>,
+    /// The style of the [`Grid`](Grid).
+    style: <Renderer::Theme as StyleSheet>::Style,
+    /// How long cells take to move to a new position, if they are animated.
+    reflow_duration: Option<Duration>,
+    /// The layouts kept in the [`State`](State), shared once the state is
+    /// created or found in the tree.
+    layout_cache: OnceCell<Rc<RefCell<LayoutCache>>>,
+}
+
+/// A cell of a [`Grid`](Grid) dropped at another position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reorder {
+    /// The index the cell was dragged from.
+    pub from: usize,
+    /// The index the cell ends up at, once it is removed from `from`.
+    pub to: usize,
+}
+
+impl Reorder {
+    /// Moves the item at `from` to `to` in `items`.
+    pub fn apply<T>(self, items: &mut Vec<T>) {
+        if self.from < items.len() && self.to < items.len() {
+            let item = items.remove(self.from);
+            items.insert(self.to, item);
+        }
+    }
+
+    /// Returns the index the item at `index` ends up at.
+    pub fn moved_index(self, index: usize) -> usize {
+        if index == self.from {
+            self.to
+        } else if self.from < index && index <= self.to {
+            index - 1
+        } else if self.to <= index && index < self.from {
+            index + 1
+        } else {
+            index
+        }
+    }
+}
+
+/// The local state of a [`Grid`](Grid).
+#[derive(Debug, Clone, Default)]
+struct State {
+    /// Whether the [`Grid`](Grid) receives keyboard input.
+    is_focused: bool,
+    /// The index of the cell with the keyboard cursor.
+    focused_cell: Option<usize>,
+    /// The cells the child trees were built for.
+    built: Built,
+    /// The indices of the selected cells.
+    selection: BTreeSet<usize>,
+    /// The cell a range selection with Shift starts from.
+    anchor: Option<usize>,
+    /// The rubber band selection following the mouse, if the left button is
+    /// held down.
+    rubber_band: Option<RubberBand>,
+    /// The cell being dragged to a new position, if any.
+    dragged_cell: Option<DraggedCell>,
+    /// The modifier keys that are currently pressed.
+    modifiers: keyboard::Modifiers,
+    /// The cell clicked last and when, to recognize double clicks.
+    last_click: Option<(usize, Instant)>,
+    /// The layouts of the keyed cells from previous layouts.
+    layout_cache: Rc<RefCell<LayoutCache>>,
+    /// The cells moving to a new position.
+    reflow: Reflow,
+}
+
+/// The cells of a [`Grid`](Grid) whose elements are built: `lines` rows, or
+/// columns if cells fill columns first, of `width` cells, one line every
+/// `stride` cells from the cell `first` on.
+///
+/// Elements are kept in slots, in order of the index of their cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Built {
+    first: usize,
+    lines: usize,
+    width: usize,
+    stride: usize,
+}
+
+impl Default for Built {
+    /// Every cell.
+    fn default() -> Self {
+        Self {
+            first: 0,
+            lines: 1,
+            width: usize::MAX,
+            stride: usize::MAX,
+        }
+    }
+}
+
+impl Built {
+    /// The cells in `range`.
+    fn range(range: Range<usize>) -> Self {
+        Self {
+            first: range.start,
+            lines: 1,
+            width: range.len(),
+            stride: range.len().max(1),
+        }
+    }
+
+    /// The `cells` of every line in `lines`, out of lines of `stride` cells.
+    fn block(lines: Range<usize>, cells: Range<usize>, stride: usize) -> Self {
+        Self {
+            first: lines.start * stride + cells.start,
+            lines: lines.len(),
+            width: cells.len(),
+            stride: stride.max(1),
+        }
+    }
+
+    /// Returns the index of the cell of the element at `slot`.
+    fn index(self, slot: usize) -> usize {
+        let width = self.width.max(1);
+
+        self.first + slot / width * self.stride + slot % width
+    }
+
+    /// Returns the slot of the element of the cell at `index`, if it is
+    /// built.
+    fn slot(self, index: usize) -> Option<usize> {
+        let offset = index.checked_sub(self.first)?;
+        let (line, cell) = (offset / self.stride, offset % self.stride);
+
+        (line < self.lines && cell < self.width).then_some(line * self.width + cell)
+    }
+
+    /// Returns the indices of the built cells, out of `len` cells.
+    fn indices(self, len: usize) -> impl Iterator<Item = usize> {
+        (0..self.lines.saturating_mul(self.width))
+            .map(move |slot| self.index(slot))
+            .take_while(move |&index| index < len)
+    }
+}
+
+/// Identifies a cell of a [`Grid`](Grid) across layouts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum CellId {
+    /// The key of the [`Cell`](Cell).
+    Key(u64),
+    /// The index of a cell without key.
+    Index(usize),
+}
+
+/// A cell moving from one position to another.
+#[derive(Debug, Clone, Copy)]
+struct Tween {
+    /// Where the cell was drawn when the movement started.
+    from: Point,
+    /// Where the cell is laid out.
+    to: Point,
+    /// When the movement started.
+    started: Instant,
+}
+
+impl Tween {
+    /// Returns where the cell is drawn at `now`, easing out over `duration`.
+    fn position(&self, now: Instant, duration: Duration) -> Point {
+        let progress = if duration.is_zero() {
+            1.0
+        } else {
+            (now.saturating_duration_since(self.started).as_secs_f32() / duration.as_secs_f32())
+                .min(1.0)
+        };
+        let eased = 1.0 - (1.0 - progress).powi(3);
+
+        self.from + (self.to - self.from) * eased
+    }
+}
+
+/// The positions of the cells of a [`Grid`](Grid), relative to its top left
+/// corner, as they are animated towards their layout.
+#[derive(Debug, Clone, Default)]
+struct Reflow {
+    tweens: HashMap<CellId, Tween>,
+    /// How far every cell is drawn from its layout in the current frame.
+    offsets: HashMap<CellId, Vector>,
+}
+
+impl Reflow {
+    /// Returns how far the cell `id` is drawn from its `target` position at
+    /// `now`.
+    ///
+    /// If the target changed, the cell starts moving from wherever it is
+    /// drawn now.
+    fn offset(&mut self, id: CellId, target: Point, now: Instant, duration: Duration) -> Vector {
+        let tween = self.tweens.entry(id).or_insert(Tween {
+            from: target,
+            to: target,
+            started: now,
+        });
+
+        if tween.to != target {
+            *tween = Tween {
+                from: tween.position(now, duration),
+                to: target,
+                started: now,
+            };
+        }
+
+        tween.position(now, duration) - target
+    }
+
+    /// Forgets the cells that are not in `ids` anymore.
+    fn retain(&mut self, ids: &HashSet<CellId>) {
+        self.tweens.retain(|id, _| ids.contains(id));
+        self.offsets.retain(|id, _| ids.contains(id));
+    }
+}
+
+/// The layouts of the keyed cells of a [`Grid`](Grid), kept across layouts.
+#[derive(Debug, Default)]
+struct LayoutCache {
+    /// The layouts of every key, most recent first, with the minimum and
+    /// maximum size they were computed for.
+    nodes: HashMap<u64, Vec<(Size, Size, Node)>>,
+    /// The row and column every cell was placed at in the last layout.
+    positions: Vec<(usize, usize)>,
+}
+
+impl LayoutCache {
+    /// Returns the layout of `key` computed for the same `limits`.
+    fn get(&self, key: u64, limits: &Limits) -> Option<Node> {
+        self.nodes.get(&key)?.iter().find_map(|(min, max, node)| {
+            (*min == limits.min() && *max == limits.max()).then(|| node.clone())
+        })
+    }
+
+    /// Keeps the `node` computed for `key` within `limits`.
+    fn insert(&mut self, key: u64, limits: &Limits, node: Node) {
+        let nodes = self.nodes.entry(key).or_default();
+        nodes.insert(0, (limits.min(), limits.max(), node));
+        nodes.truncate(CACHED_LAYOUTS);
+    }
+
+    /// Forgets the layouts of the keys that are not in `keys` anymore.
+    fn retain(&mut self, keys: &HashSet<u64>) {
+        self.nodes.retain(|key, _| keys.contains(key));
+    }
+}
+
+/// A selection rectangle dragged with the mouse.
+#[derive(Debug, Clone)]
+struct RubberBand {
+    /// Where the mouse was pressed.
+    origin: Point,
+    /// Where the mouse is now.
+    cursor: Point,
+    /// Whether the mouse has moved far enough to start selecting.
+    is_dragging: bool,
+    /// The cells that stay selected whatever the rectangle covers.
+    base: BTreeSet<usize>,
+}
+
+/// A cell dragged with the mouse to be reordered.
+#[derive(Debug, Clone, Copy)]
+struct DraggedCell {
+    /// The index of the dragged cell.
+    cell: usize,
+    /// Where the mouse was pressed.
+    origin: Point,
+    /// Where the mouse is now.
+    cursor: Point,
+    /// Whether the mouse has moved far enough to start dragging.
+    is_dragging: bool,
+}
+
+impl RubberBand {
+    /// Returns the area covered by the [`RubberBand`](RubberBand).
+    fn bounds(&self) -> Rectangle {
+        let x = self.origin.x.min(self.cursor.x);
+        let y = self.origin.y.min(self.cursor.y);
+
+        Rectangle {
+            x,
+            y,
+            width: self.origin.x.max(self.cursor.x) - x,
+            height: self.origin.y.max(self.cursor.y) - y,
+        }
+    }
+}
+
+impl Focusable for State {
+    fn is_focused(&self) -> bool {
+        self.is_focused
+    }
+
+    fn focus(&mut self) {
+        self.is_focused = true;
+    }
+
+    fn unfocus(&mut self) {
+        self.is_focused = false;
+    }
+}
+
+/// A movement of the keyboard cursor between the cells of a [`Grid`](Grid).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Movement {
+    Left,
+    Right,
+    Up,
+    Down,
+    RowStart,
+    RowEnd,
+    First,
+    Last,
+    PageUp,
+    PageDown,
+}
+
+impl Movement {
+    /// Returns the [`Movement`](Movement) bound to a key, if any.
+    fn from_key(key_code: keyboard::KeyCode, modifiers: keyboard::Modifiers) -> Option<Self> {
+        use keyboard::KeyCode;
+
+        Some(match key_code {
+            KeyCode::Left => Self::Left,
+            KeyCode::Right => Self::Right,
+            KeyCode::Up => Self::Up,
+            KeyCode::Down => Self::Down,
+            KeyCode::Home if modifiers.command() => Self::First,
+            KeyCode::End if modifiers.command() => Self::Last,
+            KeyCode::Home => Self::RowStart,
+            KeyCode::End => Self::RowEnd,
+            KeyCode::PageUp => Self::PageUp,
+            KeyCode::PageDown => Self::PageDown,
+            _ => return None,
+        })
+    }
 }
 
 /// The [`Strategy`](Strategy) of how to distribute the columns of the [`Grid`](Grid).
 enum Strategy {
     /// Use `n` columns.
     Columns(usize),
+    /// Use `n` rows, filling them column by column.
+    Rows(usize),
     /// Try to fit as much columns that have a fixed width.
     ColumnWidth(u16),
+    /// Size every column and row by its own [`Track`](Track).
+    ///
+    /// Rows beyond the given ones are sized as [`Track::Shrink`](Track::Shrink).
+    Tracks {
+        columns: Vec<Track>,
+        rows: Vec<Track>,
+    },
+    /// Fit as many columns of `column_width` as possible and put every
+    /// element at the bottom of the shortest one.
+    Masonry { column_width: u16 },
+    /// Fill rows with elements scaled to `target_height`, then scale every
+    /// full row so it spans the whole width, keeping the aspect ratio of the
+    /// elements.
+    JustifiedRows { target_height: u16 },
 }
 
 impl Default for Strategy {
@@ -28,34 +452,270 @@ impl Default for Strategy {
     }
 }
 
+/// The number of rows and columns covered by a cell of the [`Grid`](Grid).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    /// The number of rows the cell covers.
+    pub rows: usize,
+    /// The number of columns the cell covers.
+    pub columns: usize,
+}
+
+impl Span {
+    /// Creates a new [`Span`](Span) covering `rows` rows and `columns` columns.
+    pub fn new(rows: usize, columns: usize) -> Self {
+        Self { rows, columns }
+    }
+}
+
+impl Default for Span {
+    fn default() -> Self {
+        Self::new(1, 1)
+    }
+}
+
+/// How a single element is placed in the [`Grid`](Grid).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Cell {
+    /// The rows and columns covered by the cell.
+    span: Span,
+    /// Overrides the horizontal alignment of the [`Grid`](Grid).
+    align_x: Option<Horizontal>,
+    /// Overrides the vertical alignment of the [`Grid`](Grid).
+    align_y: Option<Vertical>,
+    /// Identifies the element and its version, so its layout can be reused.
+    key: Option<u64>,
+    /// Whether the cell is a section header pinned to the top of the
+    /// viewport while its section is in view.
+    is_header: bool,
+    /// Whether the element is stretched to the size of the cell.
+    fills: bool,
+}
+
+impl Cell {
+    /// Creates a [`Cell`](Cell) covering a single row and column, aligned
+    /// like the rest of the [`Grid`](Grid).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the number of rows and columns the [`Cell`](Cell) covers.
+    pub fn span(self, rows: usize, columns: usize) -> Self {
+        Self {
+            span: Span::new(rows, columns),
+            ..self
+        }
+    }
+
+    /// Sets the horizontal alignment of the element in the [`Cell`](Cell).
+    pub fn align_x(self, align_x: Horizontal) -> Self {
+        Self {
+            align_x: Some(align_x),
+            ..self
+        }
+    }
+
+    /// Sets the vertical alignment of the element in the [`Cell`](Cell).
+    pub fn align_y(self, align_y: Vertical) -> Self {
+        Self {
+            align_y: Some(align_y),
+            ..self
+        }
+    }
+
+    /// Stretches the element to the size of the [`Cell`](Cell), once the
+    /// rows and columns have been sized.
+    ///
+    /// Only the strategies using rows and columns stretch elements.
+    pub fn fill(self) -> Self {
+        Self {
+            fills: true,
+            ..self
+        }
+    }
+
+    /// Makes the [`Cell`](Cell) a section header.
+    ///
+    /// A header covers a whole row and stays at the top of the visible part
+    /// of the [`Grid`](Grid) until the cells following it, up to the next
+    /// header, are scrolled out of view. Headers are only placed on their own
+    /// row by the strategies using rows and columns. In a grid filled column
+    /// by column, a header takes a column to itself and does not stay in
+    /// view.
+    pub fn header(self) -> Self {
+        Self {
+            span: Span::new(1, usize::MAX),
+            is_header: true,
+            ..self
+        }
+    }
+
+    /// Sets the key identifying the element in the [`Cell`](Cell).
+    ///
+    /// The key must change whenever the element could be laid out
+    /// differently, as its layout is reused as long as the key and the limits
+    /// it is laid out in stay the same.
+    pub fn key(self, key: u64) -> Self {
+        Self {
+            key: Some(key),
+            ..self
+        }
+    }
+}
+
+/// How a single column or row of a [`Grid`](Grid) is sized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Track {
+    /// Use a fixed amount of units.
+    Fixed(u16),
+    /// Fill the space left over by the other tracks.
+    Fill,
+    /// Fill a share of the left over space, relative to the other filling
+    /// tracks. `Fill` is the same as `FillPortion(1)`.
+    FillPortion(u16),
+    /// Use the size of the largest cell in the track.
+    Shrink,
+}
+
+impl Track {
+    /// Returns the share of the left over space this [`Track`](Track) takes.
+    fn fill_factor(self) -> u16 {
+        match self {
+            Self::Fill => 1,
+            Self::FillPortion(factor) => factor,
+            Self::Fixed(_) | Self::Shrink => 0,
+        }
+    }
+}
+
+/// The horizontal direction the cells of a [`Grid`](Grid) are laid out in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Direction {
+    /// The first cell is on the left.
+    #[default]
+    LeftToRight,
+    /// The first cell is on the right, for right-to-left locales.
+    RightToLeft,
+}
+
+/// Where the cells of the last row of a [`Grid`](Grid) are placed when they
+/// do not fill it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Anchor {
+    /// Against the side the first cell is on.
+    #[default]
+    Start,
+    /// In the middle of the row.
+    Center,
+    /// Against the side the last cell is on.
+    End,
+}
+
+impl Anchor {
+    /// Returns which part of the free space is put before the cells.
+    fn factor(self) -> f32 {
+        match self {
+            Self::Start => 0.0,
+            Self::Center => 0.5,
+            Self::End => 1.0,
+        }
+    }
+}
+
 impl<'a, Message, Renderer> Grid<'a, Message, Renderer>
 where
     Renderer: iced_native::Renderer,
+    Renderer::Theme: StyleSheet,
 {
     /// Creates a new empty [`Grid`](Grid).
     /// Elements will be laid out in a specific amount of columns.
     #[must_use]
     pub fn with_columns(columns: usize) -> Self {
-        Self {
-            strategy: Strategy::Columns(columns),
-            elements: Vec::new(),
-        }
+        Self::with_strategy(Strategy::Columns(columns))
     }
 
     /// Creates a new empty [`Grid`](Grid).
     /// Columns will be generated to fill the given space.
     #[must_use]
     pub fn with_column_width(column_width: u16) -> Self {
+        Self::with_strategy(Strategy::ColumnWidth(column_width))
+    }
+
+    /// Creates a new empty [`Grid`](Grid) with the given number of rows.
+    /// Cells fill the first column from top to bottom, then the next one.
+    #[must_use]
+    pub fn with_rows(rows: usize) -> Self {
+        Self::with_strategy(Strategy::Rows(rows))
+    }
+
+    /// Creates a new empty [`Grid`](Grid).
+    /// Every column and row is sized by its own [`Track`](Track).
+    #[must_use]
+    pub fn with_tracks(columns: Vec<Track>, rows: Vec<Track>) -> Self {
+        Self::with_strategy(Strategy::Tracks { columns, rows })
+    }
+
+    /// Creates a new [`Grid`](Grid) of `len` cells.
+    /// The element of a cell is only built by `view` if it is in the viewport.
+    pub fn with_view<F>(len: usize, view: F) -> Self
+    where
+        F: Fn(usize) -> Element<'a, Message, Renderer> + 'a,
+    {
         Self {
-            strategy: Strategy::ColumnWidth(column_width),
-            elements: Vec::new(),
+            view: Some(Box::new(view)),
+            len,
+            ..Self::with_strategy(Strategy::default())
         }
     }
 
+    /// Creates a new empty [`Grid`](Grid).
+    /// Elements will be stacked in the shortest of as many columns as fit.
+    #[must_use]
+    pub fn with_masonry(column_width: u16) -> Self {
+        Self::with_strategy(Strategy::Masonry { column_width })
+    }
+
+    /// Creates a new empty [`Grid`](Grid).
+    /// Elements will be scaled to fill rows of about `target_height`.
+    #[must_use]
+    pub fn with_justified_rows(target_height: u16) -> Self {
+        Self::with_strategy(Strategy::JustifiedRows { target_height })
+    }
+
     pub fn with_children(children: Vec<Element<'a, Message, Renderer>>) -> Self {
         Self {
-            strategy: Strategy::default(),
+            cells: vec![Cell::default(); children.len()],
+            len: children.len(),
             elements: children,
+            ..Self::with_strategy(Strategy::default())
+        }
+    }
+
+    fn with_strategy(strategy: Strategy) -> Self {
+        Self {
+            strategy,
+            elements: Vec::new(),
+            cells: Vec::new(),
+            column_spacing: 0,
+            row_spacing: 0,
+            padding: Padding::ZERO,
+            align_x: Horizontal::Left,
+            align_y: Vertical::Top,
+            direction: Direction::default(),
+            last_row: Anchor::default(),
+            cell_size: None,
+            viewport: None,
+            view: None,
+            built: Built::default(),
+            built_columns: None,
+            len: 0,
+            on_focus_change: None,
+            on_selection_changed: None,
+            on_reorder: None,
+            on_activate: None,
+            style: Default::default(),
+            reflow_duration: None,
+            layout_cache: OnceCell::new(),
         }
     }
 
@@ -73,229 +733,3261 @@ where
         }
     }
 
-    /// Adds an [`Element`](Element) to the [`Grid`](Grid).
-    #[must_use]
-    pub fn push<E>(mut self, element: E) -> Self
-    where
-        E: Into<Element<'a, Message, Renderer>>,
-    {
-        self.elements.push(element.into());
-        self
+    pub fn rows(self, rows: usize) -> Self {
+        Self {
+            strategy: Strategy::Rows(rows),
+            ..self
+        }
     }
 
-    /// Inserts an [`Element`](Element) into the [`Grid`](Grid).
-    pub fn insert<E>(&mut self, element: E)
-    where
-        E: Into<Element<'a, Message, Renderer>>,
-    {
-        self.elements.push(element.into());
+    pub fn tracks(self, columns: Vec<Track>, rows: Vec<Track>) -> Self {
+        Self {
+            strategy: Strategy::Tracks { columns, rows },
+            ..self
+        }
     }
-}
 
-impl<'a, Message, Renderer> Widget<Message, Renderer> for Grid<'a, Message, Renderer>
-where
-    Renderer: iced_native::Renderer,
-{
-    fn children(&self) -> Vec<Tree> {
-        self.elements.iter().map(Tree::new).collect()
+    pub fn masonry(self, column_width: u16) -> Self {
+        Self {
+            strategy: Strategy::Masonry { column_width },
+            ..self
+        }
     }
 
-    fn diff(&self, tree: &mut Tree) {
-        tree.diff_children(&self.elements);
+    pub fn justified_rows(self, target_height: u16) -> Self {
+        Self {
+            strategy: Strategy::JustifiedRows { target_height },
+            ..self
+        }
     }
 
-    fn width(&self) -> Length {
-        Length::Shrink
+    /// Sets the space between both the columns and the rows of the [`Grid`](Grid).
+    pub fn spacing(self, units: u16) -> Self {
+        self.column_spacing(units).row_spacing(units)
     }
 
-    fn height(&self) -> Length {
-        Length::Shrink
+    /// Sets the horizontal space between the columns of the [`Grid`](Grid).
+    pub fn column_spacing(self, units: u16) -> Self {
+        Self {
+            column_spacing: units,
+            ..self
+        }
     }
 
-    fn layout(
-        &self,
-        renderer: &Renderer,
-        limits: &iced_native::layout::Limits,
-    ) -> iced_native::layout::Node {
-        if self.elements.is_empty() {
-            return Node::new(Size::ZERO);
+    /// Sets the vertical space between the rows of the [`Grid`](Grid).
+    pub fn row_spacing(self, units: u16) -> Self {
+        Self {
+            row_spacing: units,
+            ..self
         }
+    }
 
-        match self.strategy {
-            // find out how wide a column is by finding the widest cell in it
-            Strategy::Columns(columns) => {
-                if columns == 0 {
-                    return Node::new(Size::ZERO);
-                }
-
-                let mut layouts = Vec::with_capacity(self.elements.len());
-                let mut column_widths = Vec::<f32>::with_capacity(columns);
+    /// Sets the [`Padding`](Padding) around the cells of the [`Grid`](Grid).
+    pub fn padding<P: Into<Padding>>(self, padding: P) -> Self {
+        Self {
+            padding: padding.into(),
+            ..self
+        }
+    }
 
-                for (column, element) in (0..columns).cycle().zip(&self.elements) {
-                    let layout = element.as_widget().layout(renderer, limits);
-                    #[allow(clippy::option_if_let_else)]
-                    match column_widths.get_mut(column) {
-                        Some(column_width) => *column_width = column_width.max(layout.size().width),
-                        None => column_widths.insert(column, layout.size().width),
-                    }
+    /// Sets the horizontal alignment of the elements in their cells.
+    pub fn align_x(self, align_x: Horizontal) -> Self {
+        Self { align_x, ..self }
+    }
 
-                    layouts.push(layout);
-                }
+    /// Sets the vertical alignment of the elements in their cells.
+    pub fn align_y(self, align_y: Vertical) -> Self {
+        Self { align_y, ..self }
+    }
 
-                let column_aligns =
-                    std::iter::once(&0.)
-                        .chain(column_widths.iter())
-                        .scan(0., |state, width| {
-                            *state += width;
-                            Some(*state)
-                        });
-                let grid_width = column_widths.iter().sum();
+    /// Sets the [`Direction`](Direction) the cells are laid out in.
+    pub fn direction(self, direction: Direction) -> Self {
+        Self { direction, ..self }
+    }
 
-                build_grid(columns, column_aligns, layouts.into_iter(), grid_width)
-            }
-            // find number of columns by checking how many can fit
-            Strategy::ColumnWidth(column_width) => {
-                let column_limits = limits.width(Length::Units(column_width));
-                let column_width: f32 = column_width.into();
-                let max_width = limits.max().width;
-                let columns = (max_width / column_width).floor() as usize;
-
-                let layouts = self
-                    .elements
-                    .iter()
-                    .map(|element| element.as_widget().layout(renderer, &column_limits));
-                let column_aligns =
-                    std::iter::successors(Some(0.), |width| Some(width + column_width));
-                #[allow(clippy::cast_precision_loss)] // TODO: possible precision loss
-                let grid_width = (columns as f32) * column_width;
+    /// Sets where the cells of the last row are placed when they do not fill
+    /// it.
+    ///
+    /// This has no effect on grids filled column by column or with a masonry
+    /// layout.
+    pub fn last_row(self, last_row: Anchor) -> Self {
+        Self { last_row, ..self }
+    }
 
-                build_grid(columns, column_aligns, layouts, grid_width)
-            }
+    /// Sets the keys identifying the elements of the [`Grid`](Grid), in order.
+    ///
+    /// See [`Cell::key`](Cell::key).
+    pub fn keys(mut self, keys: impl IntoIterator<Item = u64>) -> Self {
+        if self.cells.len() < self.len {
+            self.cells.resize(self.len, Cell::default());
+        }
+        for (cell, key) in self.cells.iter_mut().zip(keys) {
+            cell.key = Some(key);
         }
+
+        self
     }
 
-    fn on_event(
-        &mut self,
-        state: &mut Tree,
-        event: Event,
-        layout: Layout<'_>,
-        cursor_position: Point,
-        renderer: &Renderer,
-        clipboard: &mut dyn Clipboard,
+    /// Animates cells moving to a new position over `duration`, for example
+    /// when the number of columns changes.
+    ///
+    /// The [`Grid`](Grid) is only redrawn when something else asks for it, so
+    /// the application should listen to `window::frames` while cells move.
+    pub fn animate_reflow(self, duration: Duration) -> Self {
+        Self {
+            reflow_duration: Some(duration),
+            ..self
+        }
+    }
+
+    /// Sets the style of the [`Grid`](Grid).
+    pub fn style(self, style: impl Into<<Renderer::Theme as StyleSheet>::Style>) -> Self {
+        Self {
+            style: style.into(),
+            ..self
+        }
+    }
+
+    /// Sets the size shared by all cells of the [`Grid`](Grid).
+    ///
+    /// Cells are then placed without being measured, so only the ones in the
+    /// [`viewport`](Self::viewport) are laid out. Spans are ignored and
+    /// [`Track`](Track)s are replaced by as many columns as fit.
+    pub fn cell_size(self, width: u16, height: u16) -> Self {
+        Self {
+            cell_size: Some(Size::new(f32::from(width), f32::from(height))),
+            ..self
+        }
+    }
+
+    /// Sets the visible part of the [`Grid`](Grid), relative to its top left
+    /// corner.
+    ///
+    /// Cells outside of it are not drawn and do not receive events. With a
+    /// [`cell_size`](Self::cell_size) they are not laid out or built either;
+    /// the width of the viewport is then used to find how many columns fit.
+    pub fn viewport(self, viewport: Rectangle) -> Self {
+        Self {
+            viewport: Some(viewport),
+            ..self
+        }
+    }
+
+    /// Sets the message produced with the index of the cell the keyboard
+    /// cursor moved to.
+    pub fn on_focus_change<F>(self, on_focus_change: F) -> Self
+    where
+        F: Fn(usize) -> Message + 'a,
+    {
+        Self {
+            on_focus_change: Some(Box::new(on_focus_change)),
+            ..self
+        }
+    }
+
+    /// Sets the message produced with the indices of the selected cells when
+    /// they change.
+    ///
+    /// Cells are selected by clicking them, with Ctrl to toggle a single cell
+    /// and Shift to select a range. Dragging from anywhere in the
+    /// [`Grid`](Grid) selects every cell touched by the dragged rectangle.
+    pub fn on_selection_changed<F>(self, on_selection_changed: F) -> Self
+    where
+        F: Fn(Vec<usize>) -> Message + 'a,
+    {
+        Self {
+            on_selection_changed: Some(Box::new(on_selection_changed)),
+            ..self
+        }
+    }
+
+    /// Sets the message produced when a cell is dragged and dropped at
+    /// another position.
+    ///
+    /// Dragging then starts from a cell moves it instead of starting a rubber
+    /// band selection.
+    pub fn on_reorder<F>(self, on_reorder: F) -> Self
+    where
+        F: Fn(Reorder) -> Message + 'a,
+    {
+        Self {
+            on_reorder: Some(Box::new(on_reorder)),
+            ..self
+        }
+    }
+
+    /// Sets the message produced with the index of a cell when it is
+    /// double-clicked, or when Enter is pressed while the keyboard cursor is
+    /// on it.
+    pub fn on_activate<F>(self, on_activate: F) -> Self
+    where
+        F: Fn(usize) -> Message + 'a,
+    {
+        Self {
+            on_activate: Some(Box::new(on_activate)),
+            ..self
+        }
+    }
+
+    /// Adds an [`Element`](Element) to the [`Grid`](Grid).
+    #[must_use]
+    pub fn push<E>(mut self, element: E) -> Self
+    where
+        E: Into<Element<'a, Message, Renderer>>,
+    {
+        self.insert(element);
+        self
+    }
+
+    /// Adds an [`Element`](Element) to the [`Grid`](Grid) that covers
+    /// `row_span` rows and `col_span` columns.
+    #[must_use]
+    pub fn push_spanning<E>(mut self, element: E, row_span: usize, col_span: usize) -> Self
+    where
+        E: Into<Element<'a, Message, Renderer>>,
+    {
+        self.insert_spanning(element, row_span, col_span);
+        self
+    }
+
+    /// Adds a section header to the [`Grid`](Grid).
+    ///
+    /// See [`Cell::header`](Cell::header).
+    #[must_use]
+    pub fn push_header<E>(self, element: E) -> Self
+    where
+        E: Into<Element<'a, Message, Renderer>>,
+    {
+        self.push_cell(element, Cell::new().header())
+    }
+
+    /// Adds an [`Element`](Element) to the [`Grid`](Grid) placed as
+    /// described by the [`Cell`](Cell).
+    #[must_use]
+    pub fn push_cell<E>(mut self, element: E, cell: Cell) -> Self
+    where
+        E: Into<Element<'a, Message, Renderer>>,
+    {
+        self.insert_cell(element, cell);
+        self
+    }
+
+    /// Inserts an [`Element`](Element) into the [`Grid`](Grid).
+    pub fn insert<E>(&mut self, element: E)
+    where
+        E: Into<Element<'a, Message, Renderer>>,
+    {
+        self.insert_cell(element, Cell::new());
+    }
+
+    /// Inserts an [`Element`](Element) into the [`Grid`](Grid) that covers
+    /// `row_span` rows and `col_span` columns.
+    pub fn insert_spanning<E>(&mut self, element: E, row_span: usize, col_span: usize)
+    where
+        E: Into<Element<'a, Message, Renderer>>,
+    {
+        self.insert_cell(element, Cell::new().span(row_span, col_span));
+    }
+
+    /// Inserts an [`Element`](Element) into the [`Grid`](Grid) placed as
+    /// described by the [`Cell`](Cell).
+    pub fn insert_cell<E>(&mut self, element: E, cell: Cell)
+    where
+        E: Into<Element<'a, Message, Renderer>>,
+    {
+        self.elements.push(element.into());
+        self.cells.push(cell);
+        self.len += 1;
+    }
+
+    /// Moves the keyboard cursor to `cell`, notifying the application if it
+    /// changed.
+    fn move_focus(&self, state: &mut State, cell: usize, shell: &mut Shell<'_, Message>) {
+        if state.focused_cell == Some(cell) {
+            return;
+        }
+
+        state.focused_cell = Some(cell);
+        if let Some(on_focus_change) = &self.on_focus_change {
+            shell.publish(on_focus_change(cell));
+        }
+    }
+
+    /// Replaces the selection, notifying the application if it changed.
+    fn select(
+        &self,
+        state: &mut State,
+        selection: BTreeSet<usize>,
         shell: &mut Shell<'_, Message>,
-    ) -> event::Status {
-        let children_status = self
-            .elements
-            .iter_mut()
-            .zip(&mut state.children)
-            .zip(layout.children())
-            .map(|((child, state), layout)| {
-                child.as_widget_mut().on_event(
-                    state,
-                    event.clone(),
-                    layout,
-                    cursor_position,
-                    renderer,
-                    clipboard,
-                    shell,
-                )
-            });
+    ) {
+        if state.selection == selection {
+            return;
+        }
 
-        children_status.fold(event::Status::Ignored, event::Status::merge)
+        state.selection = selection;
+        if let Some(on_selection_changed) = &self.on_selection_changed {
+            shell.publish(on_selection_changed(
+                state.selection.iter().copied().collect(),
+            ));
+        }
     }
 
-    fn mouse_interaction(
+    /// Draws where the `dragged_cell` would be dropped, and a copy of it
+    /// following the mouse.
+    #[allow(clippy::too_many_arguments)]
+    fn draw_dragged_cell(
         &self,
-        state: &Tree,
+        tree: &Tree,
+        dragged_cell: DraggedCell,
+        renderer: &mut Renderer,
+        theme: &Renderer::Theme,
+        style: &renderer::Style,
         layout: Layout<'_>,
-        cursor_position: Point,
         viewport: &Rectangle,
-        renderer: &Renderer,
-    ) -> mouse::Interaction {
-        self.elements
-            .iter()
-            .zip(&state.children)
-            .zip(layout.children())
-            .map(|((e, state), layout)| {
-                e.as_widget()
-                    .mouse_interaction(state, layout, cursor_position, viewport, renderer)
-            })
-            .fold(mouse::Interaction::default(), |interaction, next| {
-                interaction.max(next)
-            })
+    ) {
+        let cells: Vec<_> = layout.children().map(|child| child.bounds()).collect();
+        let cursor = dragged_cell.cursor;
+
+        let slot = layout
+            .bounds()
+            .contains(cursor)
+            .then(|| drop_slot(&cells, cursor))
+            .flatten();
+        if let Some(slot) = slot {
+            let (cell, x) = match cells.get(slot) {
+                Some(cell) => (cell, cell.x),
+                None => (&cells[slot - 1], cells[slot - 1].x + cells[slot - 1].width),
+            };
+
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds: Rectangle {
+                        x: x - DROP_INDICATOR_WIDTH / 2.0,
+                        y: cell.y,
+                        width: DROP_INDICATOR_WIDTH,
+                        height: cell.height,
+                    },
+                    border_radius: Default::default(),
+                    border_width: 0.0,
+                    border_color: Color::TRANSPARENT,
+                },
+                style.text_color,
+            );
+        }
+
+        let Some(index) = self.built.slot(dragged_cell.cell) else {
+            return;
+        };
+        let (Some(element), Some(state), Some(child)) = (
+            self.elements.get(index),
+            tree.children.get(index),
+            layout.children().nth(index),
+        ) else {
+            return;
+        };
+        if !self.in_view(layout.bounds(), child.bounds()) {
+            return;
+        }
+
+        let translation = cursor - dragged_cell.origin;
+        renderer.with_layer(*viewport, |renderer| {
+            renderer.with_translation(translation, |renderer| {
+                element
+                    .as_widget()
+                    .draw(state, renderer, theme, style, child, cursor, viewport);
+
+                draw_cell(renderer, child.bounds(), theme.selected(&self.style));
+            });
+        });
     }
 
-    fn draw(
+    /// Draws the section headers over the cells, pinning them to the top of
+    /// the `viewport` while their section is in view.
+    #[allow(clippy::too_many_arguments)]
+    fn draw_headers(
         &self,
-        state: &iced_native::widget::Tree,
+        tree: &Tree,
         renderer: &mut Renderer,
         theme: &Renderer::Theme,
-        style: &iced_native::renderer::Style,
+        style: &renderer::Style,
         layout: Layout<'_>,
         cursor_position: Point,
         viewport: &Rectangle,
     ) {
-        for ((element, state), layout) in self
-            .elements
-            .iter()
-            .zip(&state.children)
-            .zip(layout.children())
-        {
-            element.as_widget().draw(
-                state,
-                renderer,
-                theme,
-                style,
-                layout,
-                cursor_position,
-                viewport,
-            );
+        let bounds = layout.bounds();
+        let children: Vec<_> = layout.children().collect();
+        let headers: Vec<_> = (0..children.len())
+            .filter(|&index| self.cells[index].is_header)
+            .filter(|&index| self.in_view(bounds, children[index].bounds()))
+            .collect();
+        let content_bottom = bounds.y + bounds.height - f32::from(self.padding.bottom);
+
+        for (position, &index) in headers.iter().enumerate() {
+            let header = children[index].bounds();
+            let section_end = headers
+                .get(position + 1)
+                .map_or(content_bottom, |&next| children[next].bounds().y);
+            let (row, offset) = if self.is_column_major() {
+                (header, Vector::new(0.0, 0.0))
+            } else {
+                let row = Rectangle {
+                    x: bounds.x + f32::from(self.padding.left),
+                    width: bounds.width - f32::from(self.padding.horizontal()),
+                    ..header
+                };
+                let offset = Vector::new(0.0, sticky_offset(header, section_end, viewport.y));
+
+                (row, offset)
+            };
+            if (row + offset).intersection(viewport).is_none() {
+                continue;
+            }
+
+            renderer.with_layer(*viewport, |renderer| {
+                renderer.with_translation(offset, |renderer| {
+                    draw_cell(renderer, row, theme.header(&self.style));
+                    self.elements[index].as_widget().draw(
+                        &tree.children[index],
+                        renderer,
+                        theme,
+                        style,
+                        children[index],
+                        cursor_position - offset,
+                        viewport,
+                    );
+                });
+            });
         }
     }
 
-    fn overlay<'b>(
-        &'b mut self,
-        tree: &'b mut Tree,
-        layout: Layout<'_>,
-        renderer: &Renderer,
-    ) -> Option<overlay::Element<'b, Message, Renderer>> {
-        overlay::from_children(&mut self.elements, tree, layout, renderer)
+    /// Builds the elements in view, if they are built on demand.
+    fn build_in_view(mut self) -> Self {
+        if let Some(view) = self.view.take() {
+            self.built = match self.cell_size {
+                Some(cell) => {
+                    let width = self.viewport.map_or(f32::INFINITY, |v| v.width)
+                        - f32::from(self.padding.horizontal());
+                    let columns = self.fixed_columns(width, cell.width);
+                    self.built_columns = Some(columns);
+                    self.visible_cells(columns, cell)
+                }
+                None => Built::default(),
+            };
+
+            self.cells = self
+                .built
+                .indices(self.len)
+                .map(|index| self.cells.get(index).copied().unwrap_or_default())
+                .collect();
+            self.elements = self.built.indices(self.len).map(view).collect();
+        }
+
+        self
     }
-}
 
-/// Builds the layout of the [`Grid`](grid).
-fn build_grid(
-    columns: usize,
-    column_aligns: impl Iterator<Item = f32> + Clone,
-    layouts: impl Iterator<Item = Node> + ExactSizeIterator,
-    grid_width: f32,
-) -> Node {
-    let mut nodes = Vec::with_capacity(layouts.len());
-    let mut grid_height = 0.;
-    let mut row_height = 0.;
+    /// Returns the number of columns for cells of a fixed `cell_width`.
+    fn fixed_columns(&self, available_width: f32, cell_width: f32) -> usize {
+        match self.strategy {
+            Strategy::Columns(columns) => columns,
+            Strategy::Rows(0) => 0,
+            Strategy::Rows(rows) => self.len.div_ceil(rows),
+            Strategy::ColumnWidth(_)
+            | Strategy::Tracks { .. }
+            | Strategy::Masonry { .. }
+            | Strategy::JustifiedRows { .. } => {
+                fit_columns(available_width, cell_width, f32::from(self.column_spacing))
+            }
+        }
+    }
+
+    /// Returns the number of rows of cells of a fixed size laid out in
+    /// `columns`.
+    fn fixed_rows(&self, columns: usize) -> usize {
+        match self.strategy {
+            Strategy::Rows(rows) => rows.min(self.len),
+            _ => self.len.div_ceil(columns),
+        }
+    }
 
-    for ((column, column_align), mut node) in (0..columns).zip(column_aligns).cycle().zip(layouts) {
-        if column == 0 {
-            grid_height += row_height;
-            row_height = 0.;
+    /// Returns the cells of a fixed size laid out in `columns` that are in
+    /// the viewport.
+    ///
+    /// Both the rows and the columns out of view are left out, so a grid too
+    /// wide for its viewport only builds the part of its rows in view.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn visible_cells(&self, columns: usize, cell: Size) -> Built {
+        let Some(viewport) = self.viewport else {
+            return Built::default();
+        };
+        if columns == 0 {
+            return Built::range(0..0);
         }
 
-        node.move_to(Point::new(column_align, grid_height));
-        row_height = row_height.max(node.size().height);
-        nodes.push(node);
+        let gutters = self.gutters();
+        let rows = self.fixed_rows(columns);
+        // the cells are placed from the left before they are mirrored
+        let x = match self.direction {
+            Direction::LeftToRight => viewport.x,
+            Direction::RightToLeft => mirror_x(
+                self.fixed_size(columns, rows, cell).width,
+                viewport.x,
+                viewport.width,
+            ),
+        };
+        let tracks = |offset: f32, extent: f32, pitch: f32, count: usize| {
+            let first = (offset / pitch).floor().max(0.0) as usize;
+            let last = ((offset + extent) / pitch).ceil().max(0.0) as usize;
+            first.min(count)..last.min(count)
+        };
+
+        let rows_in_view = tracks(
+            viewport.y - f32::from(gutters.padding.top),
+            viewport.height,
+            cell.height + gutters.row,
+            rows,
+        );
+        let mut columns_in_view = tracks(
+            x - f32::from(gutters.padding.left),
+            viewport.width,
+            cell.width + gutters.column,
+            columns,
+        );
+
+        if self.is_column_major() {
+            return Built::block(columns_in_view, rows_in_view, rows);
+        }
+
+        // the cells of a last row anchored away from the start are shifted
+        let shift = self.last_row_shifted_columns(columns, rows);
+        if shift > 0 && rows_in_view.contains(&(rows - 1)) {
+            columns_in_view.start = columns_in_view.start.saturating_sub(shift);
+        }
+        Built::block(rows_in_view, columns_in_view, columns)
     }
 
-    grid_height += row_height;
+    /// Returns by how many columns, rounded up, the cells of the last row of
+    /// cells of a fixed size are shifted to be anchored.
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss
+    )]
+    fn last_row_shifted_columns(&self, columns: usize, rows: usize) -> usize {
+        let missing = columns * rows - self.len;
+        if self.is_column_major() || missing == 0 {
+            0
+        } else {
+            (missing as f32 * self.last_row.factor()).ceil() as usize
+        }
+    }
 
-    Node::with_children(Size::new(grid_width, grid_height), nodes)
-}
+    /// Returns the size of `rows` rows of `columns` cells of the same `cell`
+    /// size, padding included.
+    #[allow(clippy::cast_precision_loss)]
+    fn fixed_size(&self, columns: usize, rows: usize, cell: Size) -> Size {
+        let gutters = self.gutters();
 
-impl<'a, Message, Renderer> From<Grid<'a, Message, Renderer>> for Element<'a, Message, Renderer>
-where
-    Renderer: iced_native::Renderer + 'a,
-    Message: 'static,
-{
-    fn from(grid: Grid<'a, Message, Renderer>) -> Element<'a, Message, Renderer> {
-        Element::new(grid)
+        Size::new(
+            columns as f32 * cell.width
+                + gaps_size(gutters.column, columns)
+                + f32::from(gutters.padding.horizontal()),
+            rows as f32 * cell.height
+                + gaps_size(gutters.row, rows)
+                + f32::from(gutters.padding.vertical()),
+        )
+    }
+
+    /// Returns whether cells fill columns before rows.
+    fn is_column_major(&self) -> bool {
+        matches!(self.strategy, Strategy::Rows(_))
+    }
+
+    /// Returns whether a child with `bounds` is in the viewport of the
+    /// [`Grid`](Grid) laid out at `grid_bounds`.
+    ///
+    /// Children outside of it may not have been laid out, so they must be
+    /// skipped.
+    fn in_view(&self, grid_bounds: Rectangle, bounds: Rectangle) -> bool {
+        in_viewport(self.viewport, grid_bounds, bounds)
+    }
+
+    /// Lays out the elements in the shortest of as many columns of
+    /// `column_width` as fit.
+    fn layout_masonry(&self, renderer: &Renderer, limits: &Limits, column_width: u16) -> Node {
+        let gutters = self.gutters();
+        let limits = limits.pad(self.padding);
+        let cell_limits = limits.width(Length::Units(column_width));
+        let column_width = f32::from(column_width);
+        let columns = fit_columns(limits.max().width, column_width, gutters.column);
+        if columns == 0 {
+            return Node::new(Size::ZERO);
+        }
+
+        let mut layouts = self.layout_all(renderer, &cell_limits);
+
+        let (positions, height) = place_masonry(
+            layouts.iter().map(|layout| layout.size().height),
+            columns,
+            gutters.row,
+        );
+
+        let mut column_lengths = vec![0; columns];
+        self.record_positions(positions.iter().map(|&(column, _)| {
+            column_lengths[column] += 1;
+            (column_lengths[column] - 1, column)
+        }));
+
+        #[allow(clippy::cast_precision_loss)]
+        for ((node, (column, y)), (align_x, _)) in
+            layouts.iter_mut().zip(positions).zip(self.alignments())
+        {
+            let x = column as f32 * (column_width + gutters.column)
+                + alignment_offset(column_width, node.size().width, align_x);
+            node.move_to(Point::new(
+                f32::from(gutters.padding.left) + x,
+                f32::from(gutters.padding.top) + y,
+            ));
+        }
+
+        let width = span_size(&vec![column_width; columns], gutters.column, 0, columns);
+
+        Node::with_children(
+            Size::new(
+                width + f32::from(gutters.padding.horizontal()),
+                height + f32::from(gutters.padding.vertical()),
+            ),
+            layouts,
+        )
+    }
+
+    /// Lays out the elements in rows that span the whole width, scaling them
+    /// from `target_height` while keeping their aspect ratio.
+    fn layout_justified(&self, renderer: &Renderer, limits: &Limits, target_height: u16) -> Node {
+        let gutters = self.gutters();
+        let limits = limits.pad(self.padding);
+        let target_height = f32::from(target_height);
+        let available_width = limits.max().width;
+
+        let measure_limits = Limits::new(Size::ZERO, Size::new(available_width, target_height));
+        let aspect_ratios: Vec<_> = (0..self.elements.len())
+            .map(|index| {
+                let size = self.layout_element(index, renderer, &measure_limits).size();
+                if size.height > 0.0 {
+                    size.width / size.height
+                } else {
+                    1.0
+                }
+            })
+            .collect();
+
+        let rows = justify_rows(
+            &aspect_ratios,
+            available_width,
+            target_height,
+            gutters.column,
+        );
+        let alignments = self.alignments();
+
+        let mut nodes = Vec::with_capacity(self.elements.len());
+        let mut positions = Vec::with_capacity(self.elements.len());
+        let mut y = f32::from(gutters.padding.top);
+        let mut width: f32 = 0.0;
+
+        for (row_index, row) in rows.iter().enumerate() {
+            let mut x = f32::from(gutters.padding.left);
+
+            if row_index + 1 == rows.len() && available_width.is_finite() {
+                let row_width = row
+                    .cells
+                    .clone()
+                    .map(|index| aspect_ratios[index] * row.height)
+                    .sum::<f32>()
+                    + gaps_size(gutters.column, row.cells.len());
+                x += (available_width - row_width).max(0.0) * self.last_row.factor();
+            }
+
+            for index in row.cells.clone() {
+                let cell = Size::new(aspect_ratios[index] * row.height, row.height);
+                let (align_x, align_y) = alignments[index];
+
+                let mut node = self.layout_element(index, renderer, &Limits::new(Size::ZERO, cell));
+                let size = node.size();
+                node.move_to(Point::new(
+                    x + alignment_offset(cell.width, size.width, align_x),
+                    y + alignment_offset(cell.height, size.height, as_horizontal(align_y)),
+                ));
+                nodes.push(node);
+                positions.push((row_index, index - row.cells.start));
+
+                x += cell.width + gutters.column;
+            }
+
+            width = width.max(x - gutters.column - f32::from(gutters.padding.left));
+
+            y += row.height + gutters.row;
+        }
+
+        let height =
+            y - f32::from(gutters.padding.top) - if rows.is_empty() { 0.0 } else { gutters.row };
+        let width = if available_width.is_finite() {
+            available_width
+        } else {
+            width
+        };
+        self.record_positions(positions.into_iter());
+
+        Node::with_children(
+            Size::new(
+                width + f32::from(gutters.padding.horizontal()),
+                height + f32::from(gutters.padding.vertical()),
+            ),
+            nodes,
+        )
+    }
+
+    /// Lays out cells of the same `cell` size, skipping the ones out of view.
+    fn layout_fixed(&self, renderer: &Renderer, limits: &Limits, cell: Size) -> Node {
+        let gutters = self.gutters();
+        let limits = limits.pad(self.padding);
+        let columns = self
+            .built_columns
+            .unwrap_or_else(|| self.fixed_columns(limits.max().width, cell.width));
+        if columns == 0 {
+            return Node::new(Size::ZERO);
+        }
+
+        let visible = self.visible_cells(columns, cell);
+        let cell_limits = Limits::new(Size::ZERO, cell);
+        let rows = self.fixed_rows(columns);
+        let position = |index: usize| {
+            if self.is_column_major() {
+                (index % rows, index / rows)
+            } else {
+                (index / columns, index % columns)
+            }
+        };
+
+        self.record_positions((0..self.elements.len()).map(|i| position(self.built.index(i))));
+
+        let missing = columns * rows - self.len;
+        let last_row_shift = if self.is_column_major() || missing == 0 {
+            0.0
+        } else {
+            #[allow(clippy::cast_precision_loss)]
+            let free = missing as f32 * (cell.width + gutters.column);
+            free * self.last_row.factor()
+        };
+
+        #[allow(clippy::cast_precision_loss)]
+        let nodes = self
+            .alignments()
+            .into_iter()
+            .enumerate()
+            .map(|(i, (align_x, align_y))| {
+                let index = self.built.index(i);
+                let (row, column) = position(index);
+                let shift = if row + 1 == rows { last_row_shift } else { 0.0 };
+                let origin = Point::new(
+                    f32::from(gutters.padding.left)
+                        + column as f32 * (cell.width + gutters.column)
+                        + shift,
+                    f32::from(gutters.padding.top) + row as f32 * (cell.height + gutters.row),
+                );
+
+                if visible.slot(index).is_none() {
+                    let mut node = Node::new(cell);
+                    node.move_to(origin);
+                    return node;
+                }
+
+                let mut node = self.layout_element(i, renderer, &cell_limits);
+                let size = node.size();
+                node.move_to(Point::new(
+                    origin.x + alignment_offset(cell.width, size.width, align_x),
+                    origin.y + alignment_offset(cell.height, size.height, as_horizontal(align_y)),
+                ));
+                node
+            })
+            .collect();
+
+        Node::with_children(self.fixed_size(columns, rows, cell), nodes)
+    }
+
+    /// Lays out the cells from left to right.
+    fn layout_cells(&self, renderer: &Renderer, limits: &Limits) -> Node {
+        // cells of a fixed size take their space even if none is in view
+        if let Some(cell) = self.cell_size {
+            return self.layout_fixed(renderer, limits, cell);
+        }
+
+        if self.elements.is_empty() {
+            return Node::new(Size::ZERO);
+        }
+
+        let gutters = self.gutters();
+        let spans = self.cells.iter().map(|cell| cell.span);
+
+        let (placements, layouts, column_widths, row_heights) = match &self.strategy {
+            // find out how wide a column is by finding the widest cell in it
+            &Strategy::Columns(columns) => {
+                if columns == 0 {
+                    return Node::new(Size::ZERO);
+                }
+
+                let limits = limits.pad(self.padding);
+                let placements = place_cells(columns, spans);
+                let layouts = self.layout_all(renderer, &limits);
+
+                let column_widths = track_sizes(
+                    columns,
+                    gutters.column,
+                    placements
+                        .iter()
+                        .zip(&layouts)
+                        .map(|(p, layout)| (p.column, p.span.columns, layout.size().width)),
+                );
+                let row_heights = content_row_heights(&placements, &layouts, gutters.row);
+
+                (placements, layouts, column_widths, row_heights)
+            }
+            // same as columns, with rows and columns swapped
+            &Strategy::Rows(rows) => {
+                if rows == 0 {
+                    return Node::new(Size::ZERO);
+                }
+
+                let limits = limits.pad(self.padding);
+                let placements = place_cells_by_column(rows, spans);
+                let layouts = self.layout_all(renderer, &limits);
+
+                let column_widths = track_sizes(
+                    column_count(&placements),
+                    gutters.column,
+                    placements
+                        .iter()
+                        .zip(&layouts)
+                        .map(|(p, layout)| (p.column, p.span.columns, layout.size().width)),
+                );
+                let row_heights = content_row_heights(&placements, &layouts, gutters.row);
+
+                (placements, layouts, column_widths, row_heights)
+            }
+            // find number of columns by checking how many can fit
+            &Strategy::ColumnWidth(column_width) => {
+                let limits = limits.pad(self.padding);
+                let column_width = f32::from(column_width);
+                let columns = fit_columns(limits.max().width, column_width, gutters.column);
+                if columns == 0 {
+                    return Node::new(Size::ZERO);
+                }
+
+                let column_widths = vec![column_width; columns];
+                let placements = place_cells(columns, spans);
+                let layouts: Vec<_> = placements
+                    .iter()
+                    .enumerate()
+                    .map(|(index, p)| {
+                        let width =
+                            span_size(&column_widths, gutters.column, p.column, p.span.columns);
+                        let cell_limits = limits.width(Length::Units(width as u16));
+                        self.layout_element(index, renderer, &cell_limits)
+                    })
+                    .collect();
+
+                let row_heights = content_row_heights(&placements, &layouts, gutters.row);
+
+                (placements, layouts, column_widths, row_heights)
+            }
+            Strategy::Masonry { column_width } => {
+                return self.layout_masonry(renderer, limits, *column_width);
+            }
+            Strategy::JustifiedRows { target_height } => {
+                return self.layout_justified(renderer, limits, *target_height);
+            }
+            // measure the content of every track, then share out what is left
+            Strategy::Tracks { columns, rows } => {
+                if columns.is_empty() {
+                    return Node::new(Size::ZERO);
+                }
+
+                let limits = limits
+                    .width(self.width())
+                    .height(self.height())
+                    .pad(self.padding);
+                let max = limits.max();
+
+                let placements = place_cells(columns.len(), spans);
+                let rows: Vec<_> = rows
+                    .iter()
+                    .copied()
+                    .chain(std::iter::repeat(Track::Shrink))
+                    .take(row_count(&placements).max(rows.len()))
+                    .collect();
+
+                let layout_cells = |sizes: &dyn Fn(&Placement) -> Size| -> Vec<Node> {
+                    placements
+                        .iter()
+                        .enumerate()
+                        .map(|(index, placement)| {
+                            let cell_limits = Limits::new(Size::ZERO, sizes(placement));
+                            self.layout_element(index, renderer, &cell_limits)
+                        })
+                        .collect()
+                };
+
+                let layouts = layout_cells(&|_| max);
+                let content_widths = track_sizes(
+                    columns.len(),
+                    gutters.column,
+                    placements
+                        .iter()
+                        .zip(&layouts)
+                        .map(|(p, layout)| (p.column, p.span.columns, layout.size().width)),
+                );
+                let column_widths = resolve_tracks(
+                    columns,
+                    &content_widths,
+                    max.width - gaps_size(gutters.column, columns.len()),
+                );
+
+                let layouts = layout_cells(&|p| {
+                    Size::new(
+                        span_size(&column_widths, gutters.column, p.column, p.span.columns),
+                        max.height,
+                    )
+                });
+                let content_heights = content_row_heights(&placements, &layouts, gutters.row);
+                let row_heights = resolve_tracks(
+                    &rows,
+                    &content_heights,
+                    max.height - gaps_size(gutters.row, rows.len()),
+                );
+
+                let layouts = layout_cells(&|p| {
+                    Size::new(
+                        span_size(&column_widths, gutters.column, p.column, p.span.columns),
+                        span_size(&row_heights, gutters.row, p.row, p.span.rows),
+                    )
+                });
+
+                (placements, layouts, column_widths, row_heights)
+            }
+        };
+
+        self.record_positions(placements.iter().map(|p| (p.row, p.column)));
+
+        let layouts = layouts
+            .into_iter()
+            .zip(&placements)
+            .enumerate()
+            .map(|(index, (layout, p))| {
+                if !self.cells[index].fills {
+                    return layout;
+                }
+
+                let cell = Size::new(
+                    span_size(&column_widths, gutters.column, p.column, p.span.columns),
+                    span_size(&row_heights, gutters.row, p.row, p.span.rows),
+                );
+                self.layout_element(index, renderer, &Limits::new(cell, cell))
+            })
+            .collect();
+
+        build_grid(
+            &placements,
+            layouts,
+            &column_widths,
+            &row_heights,
+            &gutters,
+            &self.alignments(),
+            if self.is_column_major() {
+                Anchor::Start
+            } else {
+                self.last_row
+            },
+        )
+    }
+
+    /// Moves the cells in view towards their position in the `layout`, as
+    /// they are drawn in the frame at `now`.
+    fn advance_reflow(&self, state: &mut State, layout: Layout<'_>, now: Instant) {
+        let Some(duration) = self.reflow_duration else {
+            return;
+        };
+
+        let bounds = layout.bounds();
+        let mut ids = HashSet::new();
+
+        for (index, child) in layout.children().enumerate() {
+            let cell = child.bounds();
+            if !self.in_view(bounds, cell) {
+                continue;
+            }
+
+            let id = self.cell_id(index);
+            let target = Point::new(cell.x - bounds.x, cell.y - bounds.y);
+            let offset = state.reflow.offset(id, target, now, duration);
+            state.reflow.offsets.insert(id, offset);
+            ids.insert(id);
+        }
+        state.reflow.retain(&ids);
+    }
+
+    /// Returns how far every child is drawn from its layout while cells move
+    /// to a new position.
+    fn reflow_offsets(&self, state: &State) -> Vec<Vector> {
+        (0..self.elements.len())
+            .map(|index| {
+                let offset = state.reflow.offsets.get(&self.cell_id(index));
+                offset.copied().unwrap_or(Vector::new(0.0, 0.0))
+            })
+            .collect()
+    }
+
+    /// Returns what identifies the element at `index` across layouts.
+    fn cell_id(&self, index: usize) -> CellId {
+        self.cells[index]
+            .key
+            .map_or(CellId::Index(self.built.index(index)), CellId::Key)
+    }
+
+    /// Lays out the element at `index`, reusing the last layout computed for
+    /// the same key and `limits` if its [`Cell`](Cell) has a key.
+    fn layout_element(&self, index: usize, renderer: &Renderer, limits: &Limits) -> Node {
+        let element = &self.elements[index];
+        let (Some(key), Some(cache)) = (self.cells[index].key, self.layout_cache.get()) else {
+            return element.as_widget().layout(renderer, limits);
+        };
+
+        if let Some(node) = cache.borrow().get(key, limits) {
+            return node;
+        }
+
+        let node = element.as_widget().layout(renderer, limits);
+        cache.borrow_mut().insert(key, limits, node.clone());
+        node
+    }
+
+    /// Keeps the row and column every element is placed at, so that the
+    /// cells can be read in order whatever their alignment.
+    fn record_positions(&self, positions: impl Iterator<Item = (usize, usize)>) {
+        if let Some(cache) = self.layout_cache.get() {
+            cache.borrow_mut().positions = positions.collect();
+        }
+    }
+
+    /// Returns the row and column every element was placed at in the last
+    /// layout.
+    fn positions(&self) -> Vec<(usize, usize)> {
+        self.layout_cache
+            .get()
+            .map(|cache| cache.borrow().positions.clone())
+            .filter(|positions| positions.len() == self.elements.len())
+            .unwrap_or_else(|| (0..self.elements.len()).map(|index| (0, index)).collect())
+    }
+
+    /// Lays out every element with the same `limits`.
+    fn layout_all(&self, renderer: &Renderer, limits: &Limits) -> Vec<Node> {
+        (0..self.elements.len())
+            .map(|index| self.layout_element(index, renderer, limits))
+            .collect()
+    }
+
+    /// Returns the space around and between the cells.
+    fn gutters(&self) -> Gutters {
+        Gutters {
+            column: f32::from(self.column_spacing),
+            row: f32::from(self.row_spacing),
+            padding: self.padding,
+        }
+    }
+
+    /// Returns the alignment of every element in its cell.
+    fn alignments(&self) -> Vec<(Horizontal, Vertical)> {
+        self.cells
+            .iter()
+            .map(|cell| {
+                (
+                    cell.align_x.unwrap_or(self.align_x),
+                    cell.align_y.unwrap_or(self.align_y),
+                )
+            })
+            .collect()
+    }
+}
+
+impl<'a, Message, Renderer> Widget<Message, Renderer> for Grid<'a, Message, Renderer>
+where
+    Renderer: iced_native::Renderer,
+    Renderer::Theme: StyleSheet,
+{
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<State>()
+    }
+
+    fn state(&self) -> tree::State {
+        let layout_cache = self.layout_cache.get_or_init(Rc::default);
+
+        tree::State::new(State {
+            built: self.built,
+            layout_cache: Rc::clone(layout_cache),
+            ..State::default()
+        })
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        self.elements.iter().map(Tree::new).collect()
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        // keep the state of every cell with its index, not its position
+        let state = tree.state.downcast_mut::<State>();
+        let built = std::mem::replace(&mut state.built, self.built);
+        if built != self.built {
+            let mut children: Vec<_> = std::mem::take(&mut tree.children)
+                .into_iter()
+                .map(Some)
+                .collect();
+            tree.children = (0..self.elements.len())
+                .map(|slot| {
+                    built
+                        .slot(self.built.index(slot))
+                        .and_then(|slot| children.get_mut(slot))
+                        .and_then(Option::take)
+                        .unwrap_or_else(Tree::empty)
+                })
+                .collect();
+        }
+        tree.diff_children(&self.elements);
+
+        let cache = &tree.state.downcast_ref::<State>().layout_cache;
+        let keys = self.cells.iter().filter_map(|cell| cell.key).collect();
+        cache.borrow_mut().retain(&keys);
+        let _ = self.layout_cache.set(Rc::clone(cache));
+    }
+
+    fn width(&self) -> Length {
+        match &self.strategy {
+            Strategy::Tracks { columns, .. } if columns.iter().any(|t| t.fill_factor() > 0) => {
+                Length::Fill
+            }
+            Strategy::JustifiedRows { .. } => Length::Fill,
+            _ => Length::Shrink,
+        }
+    }
+
+    fn height(&self) -> Length {
+        match &self.strategy {
+            Strategy::Tracks { rows, .. } if rows.iter().any(|t| t.fill_factor() > 0) => {
+                Length::Fill
+            }
+            _ => Length::Shrink,
+        }
+    }
+
+    fn layout(
+        &self,
+        renderer: &Renderer,
+        limits: &iced_native::layout::Limits,
+    ) -> iced_native::layout::Node {
+        let node = self.layout_cells(renderer, limits);
+
+        match self.direction {
+            Direction::LeftToRight => node,
+            Direction::RightToLeft => mirror(&node),
+        }
+    }
+
+    fn operate(
+        &self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        operation: &mut dyn Operation<Message>,
+    ) {
+        operation.focusable(tree.state.downcast_mut::<State>(), None);
+
+        let bounds = layout.bounds();
+        let (mut children, positions): (Vec<_>, Vec<_>) = self
+            .elements
+            .iter()
+            .zip(&mut tree.children)
+            .zip(layout.children())
+            .zip(self.positions())
+            .filter(|((_, child), _)| self.in_view(bounds, child.bounds()))
+            .unzip();
+
+        operation.container(None, &mut |operation| {
+            for &index in &visual_order(&positions, false) {
+                let ((child, state), layout) = &mut children[index];
+                child
+                    .as_widget()
+                    .operate(state, *layout, renderer, operation);
+            }
+        });
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+    ) -> event::Status {
+        let bounds = layout.bounds();
+        let viewport = self.viewport;
+
+        let children_status = self
+            .elements
+            .iter_mut()
+            .zip(&mut tree.children)
+            .zip(layout.children())
+            .filter(|(_, child)| in_viewport(viewport, bounds, child.bounds()))
+            .map(|((child, state), layout)| {
+                child.as_widget_mut().on_event(
+                    state,
+                    event.clone(),
+                    layout,
+                    cursor_position,
+                    renderer,
+                    clipboard,
+                    shell,
+                )
+            })
+            .fold(event::Status::Ignored, event::Status::merge);
+
+        if children_status == event::Status::Captured {
+            return children_status;
+        }
+
+        let state = tree.state.downcast_mut::<State>();
+
+        match event {
+            Event::Window(window::Event::RedrawRequested(now)) => {
+                self.advance_reflow(state, layout, now);
+            }
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                state.is_focused = bounds.contains(cursor_position);
+                if !state.is_focused {
+                    return children_status;
+                }
+
+                let clicked = layout
+                    .children()
+                    .position(|child| child.bounds().contains(cursor_position))
+                    .map(|index| self.built.index(index));
+                let selection =
+                    select_click(&state.selection, state.anchor, clicked, state.modifiers);
+
+                if let Some(cell) = clicked {
+                    self.move_focus(state, cell, shell);
+                    if !state.modifiers.shift() {
+                        state.anchor = Some(cell);
+                    }
+
+                    let now = Instant::now();
+                    if is_double_click(state.last_click, cell, now) {
+                        state.last_click = None;
+                        if let Some(on_activate) = &self.on_activate {
+                            shell.publish(on_activate(cell));
+                        }
+                    } else {
+                        state.last_click = Some((cell, now));
+                    }
+                }
+
+                match clicked.filter(|_| self.on_reorder.is_some()) {
+                    Some(cell) => {
+                        state.dragged_cell = Some(DraggedCell {
+                            cell,
+                            origin: cursor_position,
+                            cursor: cursor_position,
+                            is_dragging: false,
+                        });
+                    }
+                    None => {
+                        state.rubber_band = Some(RubberBand {
+                            origin: cursor_position,
+                            cursor: cursor_position,
+                            is_dragging: false,
+                            base: if state.modifiers.control() {
+                                selection.clone()
+                            } else {
+                                BTreeSet::new()
+                            },
+                        });
+                    }
+                }
+                self.select(state, selection, shell);
+            }
+            Event::Mouse(mouse::Event::CursorMoved { position }) => {
+                if let Some(dragged_cell) = &mut state.dragged_cell {
+                    dragged_cell.cursor = position;
+                    dragged_cell.is_dragging |=
+                        dragged_cell.origin.distance(position) > DRAG_THRESHOLD;
+
+                    return if dragged_cell.is_dragging {
+                        event::Status::Captured
+                    } else {
+                        children_status
+                    };
+                }
+
+                let Some(rubber_band) = &mut state.rubber_band else {
+                    return children_status;
+                };
+
+                rubber_band.cursor = position;
+                rubber_band.is_dragging |= rubber_band.origin.distance(position) > DRAG_THRESHOLD;
+                if !rubber_band.is_dragging {
+                    return children_status;
+                }
+
+                let area = rubber_band.bounds();
+                let mut selection = rubber_band.base.clone();
+                selection.extend(
+                    layout
+                        .children()
+                        .enumerate()
+                        .filter(|(_, child)| self.in_view(bounds, child.bounds()))
+                        .filter(|(_, child)| child.bounds().intersection(&area).is_some())
+                        .map(|(index, _)| self.built.index(index)),
+                );
+
+                self.select(state, selection, shell);
+                return event::Status::Captured;
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                if let Some(dragged_cell) = state.dragged_cell.take() {
+                    if !dragged_cell.is_dragging {
+                        return children_status;
+                    }
+
+                    let cells: Vec<_> = layout.children().map(|child| child.bounds()).collect();
+                    let reorder = bounds
+                        .contains(cursor_position)
+                        .then(|| drop_slot(&cells, cursor_position))
+                        .flatten()
+                        .and_then(|slot| reorder_to(dragged_cell.cell, self.built.index(slot)));
+
+                    if let (Some(reorder), Some(on_reorder)) = (reorder, &self.on_reorder) {
+                        shell.publish(on_reorder(reorder));
+
+                        let moved = |cell: usize| reorder.moved_index(cell);
+                        state.focused_cell = state.focused_cell.map(moved);
+                        state.anchor = state.anchor.map(moved);
+                        let selection = state.selection.iter().copied().map(moved).collect();
+                        self.select(state, selection, shell);
+                    }
+
+                    return event::Status::Captured;
+                }
+
+                if let Some(rubber_band) = state.rubber_band.take() {
+                    if rubber_band.is_dragging {
+                        return event::Status::Captured;
+                    }
+                }
+            }
+            Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => {
+                state.modifiers = modifiers;
+            }
+            Event::Keyboard(keyboard::Event::KeyPressed {
+                key_code,
+                modifiers,
+            }) if state.is_focused => {
+                if matches!(
+                    key_code,
+                    keyboard::KeyCode::Enter | keyboard::KeyCode::NumpadEnter
+                ) {
+                    let (Some(cell), Some(on_activate)) = (state.focused_cell, &self.on_activate)
+                    else {
+                        return event::Status::Ignored;
+                    };
+
+                    shell.publish(on_activate(cell));
+                    return event::Status::Captured;
+                }
+
+                let Some(movement) = Movement::from_key(key_code, modifiers) else {
+                    return event::Status::Ignored;
+                };
+
+                let cells: Vec<_> = layout.children().map(|child| child.bounds()).collect();
+                let page = self
+                    .viewport
+                    .map_or(bounds.height, |viewport| viewport.height);
+                let current = state
+                    .focused_cell
+                    .and_then(|cell| self.built.slot(cell))
+                    .filter(|&index| index < cells.len());
+
+                let positions = self.positions();
+                let next = match current {
+                    Some(current) => {
+                        let order =
+                            visual_order(&positions, self.direction == Direction::RightToLeft);
+                        navigate(&cells, &order, current, movement, page)
+                    }
+                    None => visual_order(&positions, false).first().copied(),
+                };
+
+                if let Some(next) = next {
+                    let cell = self.built.index(next);
+                    self.move_focus(state, cell, shell);
+
+                    if modifiers.shift() {
+                        let anchor = *state.anchor.get_or_insert(cell);
+                        let selection = (anchor.min(cell)..=anchor.max(cell)).collect();
+                        self.select(state, selection, shell);
+                    } else {
+                        state.anchor = Some(cell);
+                    }
+                }
+
+                return event::Status::Captured;
+            }
+            _ => {}
+        }
+
+        children_status
+    }
+
+    fn mouse_interaction(
+        &self,
+        state: &Tree,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        let bounds = layout.bounds();
+
+        let dragged_cell = state.state.downcast_ref::<State>().dragged_cell;
+        if dragged_cell.is_some_and(|dragged_cell| dragged_cell.is_dragging) {
+            return mouse::Interaction::Grabbing;
+        }
+
+        self.elements
+            .iter()
+            .zip(&state.children)
+            .zip(layout.children())
+            .filter(|(_, child)| {
+                self.in_view(bounds, child.bounds())
+                    && child.bounds().intersection(viewport).is_some()
+            })
+            .map(|((e, state), layout)| {
+                e.as_widget()
+                    .mouse_interaction(state, layout, cursor_position, viewport, renderer)
+            })
+            .fold(mouse::Interaction::default(), |interaction, next| {
+                interaction.max(next)
+            })
+    }
+
+    fn draw(
+        &self,
+        tree: &iced_native::widget::Tree,
+        renderer: &mut Renderer,
+        theme: &Renderer::Theme,
+        style: &iced_native::renderer::Style,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+    ) {
+        let bounds = layout.bounds();
+        let state = tree.state.downcast_ref::<State>();
+        let is_dragging = state.dragged_cell.is_some_and(|cell| cell.is_dragging);
+
+        let cells: Vec<_> = layout
+            .children()
+            .map(|child| child.bounds())
+            .enumerate()
+            .filter(|&(_, cell)| self.in_view(bounds, cell))
+            .collect();
+
+        let offsets = self.reflow_offsets(state);
+
+        for &(index, cell) in &cells {
+            if cell.intersection(viewport).is_none() || self.cells[index].is_header {
+                continue;
+            }
+
+            let appearance = if state.selection.contains(&self.built.index(index)) {
+                theme.selected(&self.style)
+            } else if !is_dragging && cell.contains(cursor_position) {
+                theme.hovered(&self.style)
+            } else {
+                theme.appearance(&self.style)
+            };
+            draw_cell(renderer, cell + offsets[index], appearance);
+        }
+
+        let row_divider = theme.row_divider(&self.style);
+        let column_divider = theme.column_divider(&self.style);
+        if row_divider.is_some() || column_divider.is_some() {
+            let cells: Vec<_> = cells.iter().map(|&(_, cell)| cell).collect();
+            let rows = visual_rows(&cells);
+
+            if let Some(divider) = row_divider {
+                let content = Rectangle {
+                    x: bounds.x + f32::from(self.padding.left),
+                    width: bounds.width - f32::from(self.padding.horizontal()),
+                    ..bounds
+                };
+                for line in row_dividers(&cells, &rows, content, divider.width) {
+                    draw_divider(renderer, line, divider.color);
+                }
+            }
+            if let Some(divider) = column_divider {
+                for line in column_dividers(&cells, &rows, divider.width) {
+                    draw_divider(renderer, line, divider.color);
+                }
+            }
+        }
+
+        for (((element, state), layout), offset) in self
+            .elements
+            .iter()
+            .zip(&tree.children)
+            .zip(layout.children())
+            .zip(offsets)
+            .zip(&self.cells)
+            .filter(|(_, cell)| !cell.is_header)
+            .map(|(child, _)| child)
+            .filter(|((_, child), _)| {
+                self.in_view(bounds, child.bounds())
+                    && child.bounds().intersection(viewport).is_some()
+            })
+        {
+            let draw = |renderer: &mut Renderer| {
+                element.as_widget().draw(
+                    state,
+                    renderer,
+                    theme,
+                    style,
+                    layout,
+                    cursor_position - offset,
+                    viewport,
+                );
+            };
+
+            if offset == Vector::new(0.0, 0.0) {
+                draw(renderer);
+            } else {
+                renderer.with_translation(offset, draw);
+            }
+        }
+
+        self.draw_headers(
+            tree,
+            renderer,
+            theme,
+            style,
+            layout,
+            cursor_position,
+            viewport,
+        );
+
+        if let Some(rubber_band) = state.rubber_band.as_ref().filter(|band| band.is_dragging) {
+            draw_cell(renderer, rubber_band.bounds(), theme.selected(&self.style));
+        }
+
+        let focused = state
+            .focused_cell
+            .filter(|_| state.is_focused)
+            .and_then(|cell| self.built.slot(cell))
+            .and_then(|index| layout.children().nth(index))
+            .filter(|child| self.in_view(bounds, child.bounds()));
+
+        if let Some(focused) = focused {
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds: focused.bounds(),
+                    border_radius: Default::default(),
+                    border_width: FOCUS_RING_WIDTH,
+                    border_color: style.text_color,
+                },
+                Color::TRANSPARENT,
+            );
+        }
+
+        if let Some(dragged_cell) = state.dragged_cell.filter(|cell| cell.is_dragging) {
+            self.draw_dragged_cell(tree, dragged_cell, renderer, theme, style, layout, viewport);
+        }
+    }
+
+    fn overlay<'b>(
+        &'b mut self,
+        tree: &'b mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+    ) -> Option<overlay::Element<'b, Message, Renderer>> {
+        if self.viewport.is_none() {
+            return overlay::from_children(&mut self.elements, tree, layout, renderer);
+        }
+
+        let bounds = layout.bounds();
+        let viewport = self.viewport;
+
+        self.elements
+            .iter_mut()
+            .zip(&mut tree.children)
+            .zip(layout.children())
+            .filter(|(_, child)| in_viewport(viewport, bounds, child.bounds()))
+            .find_map(|((child, state), layout)| {
+                child.as_widget_mut().overlay(state, layout, renderer)
+            })
+    }
+}
+
+/// Where a cell of the [`Grid`](Grid) has been placed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Placement {
+    row: usize,
+    column: usize,
+    span: Span,
+}
+
+/// Places cells in reading order into the first free area they fit in.
+///
+/// Column spans are clamped to `columns` so that every cell can be placed.
+/// Row spans are clamped to the number of cells, so that a huge span does not
+/// add as many rows.
+fn place_cells(columns: usize, spans: impl Iterator<Item = Span>) -> Vec<Placement> {
+    let spans: Vec<_> = spans.collect();
+    let max_rows = spans.len();
+    let mut occupied: Vec<Vec<bool>> = Vec::new();
+    let mut placements = Vec::with_capacity(spans.len());
+    let (mut cursor_row, mut cursor_column) = (0, 0_usize);
+
+    let is_free = |occupied: &[Vec<bool>], row: usize, column: usize, span: Span| {
+        (row..row.saturating_add(span.rows)).all(|row| {
+            !occupied.get(row).is_some_and(|cells| {
+                cells[column..column.saturating_add(span.columns)]
+                    .iter()
+                    .any(|&occupied| occupied)
+            })
+        })
+    };
+
+    for span in spans {
+        let span = Span::new(span.rows.clamp(1, max_rows), span.columns.clamp(1, columns));
+
+        let (mut row, mut column) = (cursor_row, cursor_column);
+        loop {
+            if column.saturating_add(span.columns) > columns {
+                row += 1;
+                column = 0;
+            } else if is_free(&occupied, row, column, span) {
+                break;
+            } else {
+                column += 1;
+            }
+        }
+
+        let end = row.saturating_add(span.rows);
+        if occupied.len() < end {
+            occupied.resize(end, vec![false; columns]);
+        }
+        for cells in &mut occupied[row..end] {
+            cells[column..column + span.columns].fill(true);
+        }
+
+        placements.push(Placement { row, column, span });
+        cursor_row = row;
+        cursor_column = column + span.columns;
+    }
+
+    placements
+}
+
+/// Places cells column by column into the first free area they fit in.
+///
+/// Row spans are clamped to `rows` so that every cell can be placed. A cell
+/// covering every column, like a header, covers every row of its own column
+/// instead.
+fn place_cells_by_column(rows: usize, spans: impl Iterator<Item = Span>) -> Vec<Placement> {
+    let spans = spans.map(|span| {
+        if span.columns == usize::MAX {
+            Span::new(1, rows)
+        } else {
+            Span::new(span.columns, span.rows)
+        }
+    });
+
+    place_cells(rows, spans)
+        .into_iter()
+        .map(|p| Placement {
+            row: p.column,
+            column: p.row,
+            span: Span::new(p.span.columns, p.span.rows),
+        })
+        .collect()
+}
+
+/// Finds the size of `count` tracks (rows or columns) so that every cell fits
+/// in the tracks it covers.
+///
+/// Each cell is given as `(first track, number of tracks, size)`. Cells that
+/// cover a single track are sized first; any space still missing for a
+/// spanning cell is then spread evenly over the tracks it covers. The
+/// `spacing` between covered tracks counts towards the size of a cell.
+fn track_sizes(
+    count: usize,
+    spacing: f32,
+    cells: impl Iterator<Item = (usize, usize, f32)>,
+) -> Vec<f32> {
+    let mut cells: Vec<_> = cells.collect();
+    cells.sort_by_key(|&(_, span, _)| span);
+
+    let mut tracks = vec![0.0_f32; count];
+    for (start, span, size) in cells {
+        let end = (start + span).min(count);
+        let tracks = &mut tracks[start..end];
+        let current = tracks.iter().sum::<f32>() + gaps_size(spacing, tracks.len());
+
+        if size > current {
+            #[allow(clippy::cast_precision_loss)]
+            let extra = (size - current) / tracks.len() as f32;
+            tracks.iter_mut().for_each(|track| *track += extra);
+        }
+    }
+
+    tracks
+}
+
+/// The space around and between the cells of a [`Grid`](Grid).
+#[derive(Debug, Clone, Copy)]
+struct Gutters {
+    column: f32,
+    row: f32,
+    padding: Padding,
+}
+
+/// Returns the space taken by the gaps between `tracks` tracks.
+#[allow(clippy::cast_precision_loss)]
+fn gaps_size(spacing: f32, tracks: usize) -> f32 {
+    spacing * tracks.saturating_sub(1) as f32
+}
+
+/// How many layouts of a cell are kept, as a cell can be laid out with
+/// different limits during a single layout of the [`Grid`](Grid).
+const CACHED_LAYOUTS: usize = 4;
+
+/// The width of the border drawn around the cell with the keyboard cursor.
+const FOCUS_RING_WIDTH: f32 = 2.0;
+
+/// How far the mouse has to move before a rubber band selection or a drag
+/// starts.
+const DRAG_THRESHOLD: f32 = 4.0;
+
+/// The width of the line showing where a dragged cell would be dropped.
+const DROP_INDICATOR_WIDTH: f32 = 2.0;
+
+/// The longest time between two clicks on a cell for them to be a double
+/// click.
+const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Returns whether clicking `cell` at `now` makes a double click with the
+/// `last_click`.
+fn is_double_click(last_click: Option<(usize, Instant)>, cell: usize, now: Instant) -> bool {
+    last_click.is_some_and(|(last, at)| {
+        last == cell && now.saturating_duration_since(at) <= DOUBLE_CLICK_INTERVAL
+    })
+}
+
+/// Returns the position among `cells` a cell dropped at `cursor` is inserted
+/// at, from `0` to `cells.len()`.
+///
+/// The cell under the cursor, or else the closest one, is split in half: the
+/// dropped cell goes before it on its left half and after it on its right.
+fn drop_slot(cells: &[Rectangle], cursor: Point) -> Option<usize> {
+    let target = cells
+        .iter()
+        .position(|cell| cell.contains(cursor))
+        .or_else(|| {
+            (0..cells.len()).min_by(by_key(|index| cells[index].center().distance(cursor)))
+        })?;
+
+    let after = cursor.x > cells[target].center().x;
+    Some(target + usize::from(after))
+}
+
+/// Returns the [`Reorder`](Reorder) of moving the cell at `from` to before the
+/// cell at `slot`, if it moves at all.
+fn reorder_to(from: usize, slot: usize) -> Option<Reorder> {
+    let to = if slot > from { slot - 1 } else { slot };
+
+    (to != from).then_some(Reorder { from, to })
+}
+
+/// Returns the selection after clicking on the `clicked` cell, or on no cell
+/// at all.
+///
+/// With Ctrl the clicked cell is toggled, with Shift every cell from the
+/// `anchor` is selected, and otherwise only the clicked cell is.
+fn select_click(
+    selection: &BTreeSet<usize>,
+    anchor: Option<usize>,
+    clicked: Option<usize>,
+    modifiers: keyboard::Modifiers,
+) -> BTreeSet<usize> {
+    let Some(clicked) = clicked else {
+        return if modifiers.control() || modifiers.shift() {
+            selection.clone()
+        } else {
+            BTreeSet::new()
+        };
+    };
+
+    match anchor {
+        Some(anchor) if modifiers.shift() => {
+            let range = anchor.min(clicked)..=anchor.max(clicked);
+            if modifiers.control() {
+                selection.iter().copied().chain(range).collect()
+            } else {
+                range.collect()
+            }
+        }
+        _ if modifiers.control() => {
+            let mut selection = selection.clone();
+            if !selection.remove(&clicked) {
+                selection.insert(clicked);
+            }
+            selection
+        }
+        _ => BTreeSet::from([clicked]),
+    }
+}
+
+/// Returns whether a child with `bounds` intersects the `viewport` of a
+/// [`Grid`](Grid) laid out at `grid_bounds`.
+fn in_viewport(viewport: Option<Rectangle>, grid_bounds: Rectangle, bounds: Rectangle) -> bool {
+    viewport.is_none_or(|viewport| {
+        let viewport = Rectangle {
+            x: grid_bounds.x + viewport.x,
+            y: grid_bounds.y + viewport.y,
+            ..viewport
+        };
+
+        bounds.intersection(&viewport).is_some()
+    })
+}
+
+/// Finds the cell the keyboard cursor moves to from `current`, given the
+/// `cells` bounds and the `order` they are read in from left to right. A page
+/// is `page` units tall.
+fn navigate(
+    cells: &[Rectangle],
+    order: &[usize],
+    current: usize,
+    movement: Movement,
+    page: f32,
+) -> Option<usize> {
+    let current_bounds = *cells.get(current)?;
+    let center = current_bounds.center();
+    let position = order.iter().position(|&index| index == current)?;
+
+    let in_row_of = |row: Rectangle| move |index: &usize| overlaps_vertically(cells[*index], row);
+    let horizontal_distance = |index: usize| (cells[index].center().x - center.x).abs();
+
+    match movement {
+        Movement::Left => position.checked_sub(1).map(|position| order[position]),
+        Movement::Right => order.get(position + 1).copied(),
+        Movement::First => order.first().copied(),
+        Movement::Last => order.last().copied(),
+        Movement::RowStart => (0..cells.len())
+            .filter(in_row_of(current_bounds))
+            .min_by(by_key(|index| cells[index].x)),
+        Movement::RowEnd => (0..cells.len())
+            .filter(in_row_of(current_bounds))
+            .max_by(by_key(|index| cells[index].x)),
+        Movement::Up | Movement::Down | Movement::PageUp | Movement::PageDown => {
+            let direction = match movement {
+                Movement::Up | Movement::PageUp => -1.0,
+                _ => 1.0,
+            };
+            let distance = |index: usize| (cells[index].center().y - center.y) * direction;
+            let candidates: Vec<_> = (0..cells.len())
+                .filter(|&index| distance(index) > 0.5)
+                .collect();
+
+            let nearest = candidates.iter().copied().min_by(by_key(distance));
+            let target = match movement {
+                Movement::PageUp | Movement::PageDown => candidates
+                    .iter()
+                    .copied()
+                    .filter(|&index| distance(index) <= page)
+                    .max_by(by_key(distance))
+                    .or(nearest),
+                _ => nearest,
+            }?;
+
+            candidates
+                .into_iter()
+                .filter(in_row_of(cells[target]))
+                .min_by(by_key(horizontal_distance))
+        }
+    }
+}
+
+/// Returns whether two cells share part of a row.
+fn overlaps_vertically(a: Rectangle, b: Rectangle) -> bool {
+    a.y < b.y + b.height && b.y < a.y + a.height
+}
+
+/// Compares two indices by the `key` computed for each of them.
+fn by_key(key: impl Fn(usize) -> f32) -> impl Fn(&usize, &usize) -> Ordering {
+    move |a, b| key(*a).total_cmp(&key(*b))
+}
+
+/// Returns the order in which cells placed at the given `positions`, as row
+/// and column, are read: row by row, then column by column, starting from
+/// the last column if `reversed`.
+fn visual_order(positions: &[(usize, usize)], reversed: bool) -> Vec<usize> {
+    let mut order: Vec<_> = (0..positions.len()).collect();
+    order.sort_by(|&a, &b| {
+        let ((a_row, a_column), (b_row, b_column)) = (positions[a], positions[b]);
+        let columns = if reversed {
+            b_column.cmp(&a_column)
+        } else {
+            a_column.cmp(&b_column)
+        };
+        a_row.cmp(&b_row).then(columns)
+    });
+
+    order
+}
+
+/// Draws the background and border of a cell with the given `appearance`.
+fn draw_cell<Renderer>(renderer: &mut Renderer, bounds: Rectangle, appearance: style::Appearance)
+where
+    Renderer: iced_native::Renderer,
+{
+    if appearance.background.is_none() && appearance.border_width <= 0.0 {
+        return;
+    }
+
+    renderer.fill_quad(
+        renderer::Quad {
+            bounds,
+            border_radius: appearance.border_radius.into(),
+            border_width: appearance.border_width,
+            border_color: appearance.border_color,
+        },
+        appearance
+            .background
+            .unwrap_or(Background::Color(Color::TRANSPARENT)),
+    );
+}
+
+/// Draws a divider line covering `bounds`.
+fn draw_divider<Renderer>(renderer: &mut Renderer, bounds: Rectangle, color: Color)
+where
+    Renderer: iced_native::Renderer,
+{
+    renderer.fill_quad(
+        renderer::Quad {
+            bounds,
+            border_radius: Default::default(),
+            border_width: 0.0,
+            border_color: Color::TRANSPARENT,
+        },
+        color,
+    );
+}
+
+/// Returns how far a `header` is moved down to stay at the `top` of the
+/// viewport, without leaving its section ending at `section_end`.
+fn sticky_offset(header: Rectangle, section_end: f32, top: f32) -> f32 {
+    (top.min(section_end - header.height) - header.y).max(0.0)
+}
+
+/// Groups the `cells` into the rows they are seen in, each in reading order.
+///
+/// A cell starts a new row if it is below every cell of the current one.
+fn visual_rows(cells: &[Rectangle]) -> Vec<Vec<usize>> {
+    let mut rows: Vec<Vec<usize>> = Vec::new();
+    let mut bottom = f32::NEG_INFINITY;
+
+    let mut order: Vec<_> = (0..cells.len()).collect();
+    order.sort_by(by_key(|index| cells[index].y));
+
+    for index in order {
+        let cell = cells[index];
+        match rows.last_mut() {
+            Some(row) if cell.y < bottom => row.push(index),
+            _ => rows.push(vec![index]),
+        }
+        bottom = bottom.max(cell.y + cell.height);
+    }
+
+    for row in &mut rows {
+        row.sort_by(by_key(|index| cells[index].x));
+    }
+
+    rows
+}
+
+/// Returns the vertical span of a row of `cells`.
+fn row_extent(cells: &[Rectangle], row: &[usize]) -> (f32, f32) {
+    row.iter().fold(
+        (f32::INFINITY, f32::NEG_INFINITY),
+        |(top, bottom), &index| {
+            let cell = cells[index];
+            (top.min(cell.y), bottom.max(cell.y + cell.height))
+        },
+    )
+}
+
+/// Returns the lines of the given `width` centered between two `rows`,
+/// spanning the `content` of the grid.
+fn row_dividers(
+    cells: &[Rectangle],
+    rows: &[Vec<usize>],
+    content: Rectangle,
+    width: f32,
+) -> Vec<Rectangle> {
+    rows.windows(2)
+        .map(|rows| {
+            let (_, bottom) = row_extent(cells, &rows[0]);
+            let (top, _) = row_extent(cells, &rows[1]);
+
+            Rectangle {
+                x: content.x,
+                y: (bottom + top - width) / 2.0,
+                width: content.width,
+                height: width,
+            }
+        })
+        .collect()
+}
+
+/// Returns the lines of the given `width` centered between two neighbouring
+/// cells of the same row, as tall as the row.
+fn column_dividers(cells: &[Rectangle], rows: &[Vec<usize>], width: f32) -> Vec<Rectangle> {
+    rows.iter()
+        .flat_map(|row| {
+            let (top, bottom) = row_extent(cells, row);
+
+            row.windows(2).map(move |pair| {
+                let (left, right) = (cells[pair[0]], cells[pair[1]]);
+
+                Rectangle {
+                    x: (left.x + left.width + right.x - width) / 2.0,
+                    y: top,
+                    width,
+                    height: bottom - top,
+                }
+            })
+        })
+        .collect()
+}
+
+/// Places cells of the given `heights` one by one at the bottom of the
+/// shortest of `columns` columns.
+///
+/// Returns the column and vertical offset of every cell, and the height of the
+/// tallest column.
+fn place_masonry(
+    heights: impl Iterator<Item = f32>,
+    columns: usize,
+    spacing: f32,
+) -> (Vec<(usize, f32)>, f32) {
+    let mut bottoms = vec![0.0_f32; columns];
+    let mut is_empty = true;
+
+    let positions = heights
+        .map(|height| {
+            let column = (0..columns)
+                .min_by(by_key(|column| bottoms[column]))
+                .unwrap_or(0);
+            let y = bottoms[column];
+            bottoms[column] += height + spacing;
+            is_empty = false;
+
+            (column, y)
+        })
+        .collect();
+
+    let tallest = bottoms.iter().copied().fold(0.0, f32::max);
+    let height = if is_empty { 0.0 } else { tallest - spacing };
+
+    (positions, height)
+}
+
+/// A row of cells laid out with [`Strategy::JustifiedRows`](Strategy).
+#[derive(Debug, Clone, PartialEq)]
+struct JustifiedRow {
+    /// The indices of the cells in the row.
+    cells: Range<usize>,
+    /// The height every cell in the row is scaled to.
+    height: f32,
+}
+
+/// Splits cells with the given `aspect_ratios` into rows that fill `width`.
+///
+/// Cells are added to a row at `target_height` until they are at least as
+/// wide as `width`, then the row is scaled down to fit exactly. The last row
+/// keeps the `target_height` if it is not full.
+fn justify_rows(
+    aspect_ratios: &[f32],
+    width: f32,
+    target_height: f32,
+    spacing: f32,
+) -> Vec<JustifiedRow> {
+    let mut rows = Vec::new();
+    let mut start = 0;
+    let mut row_width = 0.0;
+
+    for (index, aspect_ratio) in aspect_ratios.iter().enumerate() {
+        row_width += aspect_ratio * target_height;
+        let available = width - gaps_size(spacing, index + 1 - start);
+
+        if row_width >= available && available > 0.0 {
+            rows.push(JustifiedRow {
+                cells: start..index + 1,
+                height: target_height * available / row_width,
+            });
+            start = index + 1;
+            row_width = 0.0;
+        }
+    }
+
+    if start < aspect_ratios.len() {
+        rows.push(JustifiedRow {
+            cells: start..aspect_ratios.len(),
+            height: target_height,
+        });
+    }
+
+    rows
+}
+
+/// Returns how many columns of `column_width` fit in `available_width`.
+fn fit_columns(available_width: f32, column_width: f32, spacing: f32) -> usize {
+    ((available_width + spacing) / (column_width + spacing)).floor() as usize
+}
+
+/// Returns the number of rows needed by the `placements`.
+fn row_count(placements: &[Placement]) -> usize {
+    placements
+        .iter()
+        .map(|p| p.row + p.span.rows)
+        .max()
+        .unwrap_or(0)
+}
+
+/// Returns the number of columns needed by the `placements`.
+fn column_count(placements: &[Placement]) -> usize {
+    placements
+        .iter()
+        .map(|p| p.column + p.span.columns)
+        .max()
+        .unwrap_or(0)
+}
+
+/// Returns the total size of `span` tracks starting at `start`, including the
+/// `spacing` between them.
+fn span_size(tracks: &[f32], spacing: f32, start: usize, span: usize) -> f32 {
+    let tracks = &tracks[start.min(tracks.len())..(start + span).min(tracks.len())];
+    tracks.iter().sum::<f32>() + gaps_size(spacing, tracks.len())
+}
+
+/// Finds the height of every row from the height of the cells in it.
+fn content_row_heights(placements: &[Placement], layouts: &[Node], spacing: f32) -> Vec<f32> {
+    track_sizes(
+        row_count(placements),
+        spacing,
+        placements
+            .iter()
+            .zip(layouts)
+            .map(|(p, layout)| (p.row, p.span.rows, layout.size().height)),
+    )
+}
+
+/// Sizes every [`Track`](Track) given the size of its `content` and the
+/// `available` space.
+///
+/// Filling tracks share whatever is left after the fixed and shrinking
+/// tracks. If the available space is unbounded they shrink to their content
+/// instead.
+fn resolve_tracks(tracks: &[Track], content: &[f32], available: f32) -> Vec<f32> {
+    let mut sizes: Vec<f32> = tracks
+        .iter()
+        .zip(content.iter().chain(std::iter::repeat(&0.0)))
+        .map(|(track, &content)| match track {
+            Track::Fixed(units) => f32::from(*units),
+            Track::Shrink => content,
+            Track::Fill | Track::FillPortion(_) => 0.0,
+        })
+        .collect();
+
+    let total_factor: u32 = tracks.iter().map(|t| u32::from(t.fill_factor())).sum();
+    if total_factor == 0 {
+        return sizes;
+    }
+
+    let remaining = available - sizes.iter().sum::<f32>();
+    for ((size, track), &content) in sizes
+        .iter_mut()
+        .zip(tracks)
+        .zip(content.iter().chain(std::iter::repeat(&0.0)))
+    {
+        let factor = track.fill_factor();
+        if factor == 0 {
+            continue;
+        }
+
+        *size = if remaining.is_finite() {
+            #[allow(clippy::cast_precision_loss)]
+            let share = f32::from(factor) / total_factor as f32;
+            remaining.max(0.0) * share
+        } else {
+            content
+        };
+    }
+
+    sizes
+}
+
+/// Returns how far an element of `size` is moved to be aligned in `space`.
+fn alignment_offset(space: f32, size: f32, alignment: Horizontal) -> f32 {
+    match alignment {
+        Horizontal::Left => 0.0,
+        Horizontal::Center => ((space - size) / 2.0).max(0.0),
+        Horizontal::Right => (space - size).max(0.0),
+    }
+}
+
+/// Converts a [`Vertical`](Vertical) alignment to the matching
+/// [`Horizontal`](Horizontal) one, so both axes can share the same math.
+fn as_horizontal(alignment: Vertical) -> Horizontal {
+    match alignment {
+        Vertical::Top => Horizontal::Left,
+        Vertical::Center => Horizontal::Center,
+        Vertical::Bottom => Horizontal::Right,
+    }
+}
+
+/// Returns the space left free at the end of the last row.
+///
+/// This is zero if a cell from a previous row reaches into the last one, as
+/// its cells could then not be moved together.
+fn last_row_space(placements: &[Placement], column_widths: &[f32], spacing: f32) -> f32 {
+    let last = row_count(placements).saturating_sub(1);
+    let reaches_last_row = |p: &&Placement| p.row + p.span.rows > last;
+    if placements
+        .iter()
+        .filter(reaches_last_row)
+        .any(|p| p.row != last)
+    {
+        return 0.0;
+    }
+
+    let end = placements
+        .iter()
+        .filter(reaches_last_row)
+        .map(|p| p.column + p.span.columns)
+        .max()
+        .unwrap_or(0);
+    if end >= column_widths.len() {
+        return 0.0;
+    }
+
+    span_size(column_widths, spacing, end, column_widths.len() - end) + spacing
+}
+
+/// Flips the children of a laid out `node` horizontally.
+fn mirror(node: &Node) -> Node {
+    let width = node.size().width;
+    let children = node
+        .children()
+        .iter()
+        .map(|child| {
+            let bounds = child.bounds();
+            let mut child = child.clone();
+            child.move_to(Point::new(
+                mirror_x(width, bounds.x, bounds.width),
+                bounds.y,
+            ));
+            child
+        })
+        .collect();
+
+    Node::with_children(node.size(), children)
+}
+
+/// Returns where something `width` wide at `x` ends up once the `node_width`
+/// wide node it is in is flipped horizontally.
+fn mirror_x(node_width: f32, x: f32, width: f32) -> f32 {
+    node_width - x - width
+}
+
+/// Builds the layout of the [`Grid`](grid).
+fn build_grid(
+    placements: &[Placement],
+    layouts: Vec<Node>,
+    column_widths: &[f32],
+    row_heights: &[f32],
+    gutters: &Gutters,
+    alignments: &[(Horizontal, Vertical)],
+    last_row: Anchor,
+) -> Node {
+    let offsets = |tracks: &[f32], start: f32, spacing: f32| -> Vec<f32> {
+        tracks
+            .iter()
+            .scan(start, |state, size| {
+                let offset = *state;
+                *state += size + spacing;
+                Some(offset)
+            })
+            .collect()
+    };
+    let column_aligns = offsets(
+        column_widths,
+        f32::from(gutters.padding.left),
+        gutters.column,
+    );
+    let row_aligns = offsets(row_heights, f32::from(gutters.padding.top), gutters.row);
+    let last_row_index = row_count(placements).saturating_sub(1);
+    let last_row_shift =
+        last_row_space(placements, column_widths, gutters.column) * last_row.factor();
+
+    let nodes = placements
+        .iter()
+        .zip(layouts)
+        .zip(alignments)
+        .map(|((p, mut node), &(align_x, align_y))| {
+            let width = span_size(column_widths, gutters.column, p.column, p.span.columns);
+            let height = span_size(row_heights, gutters.row, p.row, p.span.rows);
+            let size = node.size();
+            let shift = if p.row == last_row_index {
+                last_row_shift
+            } else {
+                0.0
+            };
+
+            node.move_to(Point::new(
+                column_aligns[p.column] + shift + alignment_offset(width, size.width, align_x),
+                row_aligns[p.row] + alignment_offset(height, size.height, as_horizontal(align_y)),
+            ));
+            node
+        })
+        .collect();
+
+    let grid_width = span_size(column_widths, gutters.column, 0, column_widths.len())
+        + f32::from(gutters.padding.horizontal());
+    let grid_height = span_size(row_heights, gutters.row, 0, row_heights.len())
+        + f32::from(gutters.padding.vertical());
+
+    Node::with_children(Size::new(grid_width, grid_height), nodes)
+}
+
+impl<'a, Message, Renderer> From<Grid<'a, Message, Renderer>> for Element<'a, Message, Renderer>
+where
+    Renderer: iced_native::Renderer + 'a,
+    Renderer::Theme: StyleSheet,
+    Message: 'static,
+{
+    fn from(grid: Grid<'a, Message, Renderer>) -> Element<'a, Message, Renderer> {
+        Element::new(grid.build_in_view())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{self, assert_cell_bounds, assert_cells, assert_size, rect, stub, Stub};
+
+    type TestGrid<'a> = Grid<'a, (), testing::Renderer>;
+
+    #[test]
+    fn place_cells_without_spans_in_reading_order() {
+        let placements = place_cells(3, std::iter::repeat_n(Span::default(), 4));
+
+        let positions: Vec<_> = placements.iter().map(|p| (p.row, p.column)).collect();
+        assert_eq!(positions, vec![(0, 0), (0, 1), (0, 2), (1, 0)]);
+    }
+
+    #[test]
+    fn place_cells_packs_around_spanning_cell() {
+        let spans = vec![
+            Span::new(2, 2),
+            Span::default(),
+            Span::default(),
+            Span::default(),
+            Span::default(),
+        ];
+
+        let placements = place_cells(3, spans.into_iter());
+
+        let positions: Vec<_> = placements.iter().map(|p| (p.row, p.column)).collect();
+        assert_eq!(positions, vec![(0, 0), (0, 2), (1, 2), (2, 0), (2, 1)]);
+    }
+
+    #[test]
+    fn place_cells_wraps_span_that_does_not_fit() {
+        let spans = vec![Span::default(), Span::default(), Span::new(1, 2)];
+
+        let placements = place_cells(3, spans.into_iter());
+
+        let positions: Vec<_> = placements.iter().map(|p| (p.row, p.column)).collect();
+        assert_eq!(positions, vec![(0, 0), (0, 1), (1, 0)]);
+    }
+
+    #[test]
+    fn place_cells_clamps_span_to_columns() {
+        let placements = place_cells(2, std::iter::once(Span::new(1, 5)));
+
+        assert_eq!(placements[0].span, Span::new(1, 2));
+    }
+
+    #[test]
+    fn place_cells_clamps_huge_spans() {
+        let spans = [Span::new(usize::MAX, usize::MAX), Span::default()];
+
+        let placements = place_cells(3, spans.into_iter());
+
+        assert_eq!(placements[0].span, Span::new(2, 3));
+        assert_eq!((placements[1].row, placements[1].column), (2, 0));
+        assert_eq!(row_count(&placements), 3);
+    }
+
+    #[test]
+    fn track_sizes_spreads_spanning_cells() {
+        let cells = vec![(0, 1, 10.0), (1, 1, 20.0), (0, 2, 50.0)];
+
+        let tracks = track_sizes(2, 0.0, cells.into_iter());
+
+        assert_eq!(tracks, vec![20.0, 30.0]);
+    }
+
+    #[test]
+    fn track_sizes_counts_spacing_towards_spanning_cells() {
+        let cells = vec![(0, 1, 10.0), (1, 1, 10.0), (0, 2, 30.0)];
+
+        let tracks = track_sizes(2, 4.0, cells.into_iter());
+
+        assert_eq!(tracks, vec![13.0, 13.0]);
+    }
+
+    #[test]
+    fn build_grid_positions_spanning_cells() {
+        let placements = place_cells(
+            2,
+            vec![Span::new(2, 1), Span::default(), Span::default()].into_iter(),
+        );
+        let layouts = vec![
+            Node::new(Size::new(10.0, 30.0)),
+            Node::new(Size::new(20.0, 10.0)),
+            Node::new(Size::new(20.0, 10.0)),
+        ];
+
+        let gutters = Gutters {
+            column: 0.0,
+            row: 0.0,
+            padding: Padding::ZERO,
+        };
+        let alignments = vec![(Horizontal::Left, Vertical::Top); 3];
+
+        let row_heights = content_row_heights(&placements, &layouts, gutters.row);
+        let grid = build_grid(
+            &placements,
+            layouts,
+            &[10.0, 20.0],
+            &row_heights,
+            &gutters,
+            &alignments,
+            Anchor::Start,
+        );
+
+        let bounds: Vec<_> = grid.children().iter().map(Node::bounds).collect();
+        assert_eq!(grid.size(), Size::new(30.0, 30.0));
+        assert_eq!(
+            bounds,
+            vec![
+                Rectangle::new(Point::new(0.0, 0.0), Size::new(10.0, 30.0)),
+                Rectangle::new(Point::new(10.0, 0.0), Size::new(20.0, 10.0)),
+                Rectangle::new(Point::new(10.0, 15.0), Size::new(20.0, 10.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn resolve_tracks_shares_left_over_space() {
+        let tracks = [
+            Track::Fixed(120),
+            Track::FillPortion(2),
+            Track::Fill,
+            Track::Shrink,
+        ];
+        let content = [10.0, 10.0, 10.0, 30.0];
+
+        let sizes = resolve_tracks(&tracks, &content, 450.0);
+
+        assert_eq!(sizes, vec![120.0, 200.0, 100.0, 30.0]);
+    }
+
+    #[test]
+    fn resolve_tracks_never_shrinks_fill_below_zero() {
+        let tracks = [Track::Fixed(100), Track::Fill];
+
+        let sizes = resolve_tracks(&tracks, &[0.0, 50.0], 80.0);
+
+        assert_eq!(sizes, vec![100.0, 0.0]);
+    }
+
+    #[test]
+    fn resolve_tracks_fill_uses_content_when_unbounded() {
+        let tracks = [Track::Fixed(100), Track::Fill];
+
+        let sizes = resolve_tracks(&tracks, &[0.0, 50.0], f32::INFINITY);
+
+        assert_eq!(sizes, vec![100.0, 50.0]);
+    }
+
+    #[test]
+    fn build_grid_applies_gutters_and_alignment() {
+        let placements = place_cells(2, std::iter::repeat_n(Span::default(), 3));
+        let layouts = vec![
+            Node::new(Size::new(10.0, 10.0)),
+            Node::new(Size::new(20.0, 20.0)),
+            Node::new(Size::new(10.0, 10.0)),
+        ];
+        let gutters = Gutters {
+            column: 5.0,
+            row: 8.0,
+            padding: Padding::new(2),
+        };
+        let alignments = vec![
+            (Horizontal::Center, Vertical::Center),
+            (Horizontal::Left, Vertical::Top),
+            (Horizontal::Right, Vertical::Bottom),
+        ];
+
+        let row_heights = content_row_heights(&placements, &layouts, gutters.row);
+        let grid = build_grid(
+            &placements,
+            layouts,
+            &[20.0, 20.0],
+            &row_heights,
+            &gutters,
+            &alignments,
+            Anchor::Start,
+        );
+
+        let positions: Vec<_> = grid
+            .children()
+            .iter()
+            .map(|n| n.bounds().position())
+            .collect();
+        assert_eq!(grid.size(), Size::new(49.0, 42.0));
+        assert_eq!(
+            positions,
+            vec![
+                Point::new(7.0, 7.0),
+                Point::new(27.0, 2.0),
+                Point::new(12.0, 30.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn in_viewport_is_relative_to_grid() {
+        let grid = Rectangle::new(Point::new(100.0, 100.0), Size::new(300.0, 1000.0));
+        let viewport = Some(Rectangle::new(
+            Point::new(0.0, 200.0),
+            Size::new(300.0, 100.0),
+        ));
+        let cell = |y| Rectangle::new(Point::new(100.0, y), Size::new(50.0, 50.0));
+
+        assert!(!in_viewport(viewport, grid, cell(240.0)));
+        assert!(in_viewport(viewport, grid, cell(280.0)));
+        assert!(in_viewport(viewport, grid, cell(380.0)));
+        assert!(!in_viewport(viewport, grid, cell(400.0)));
+        assert!(in_viewport(None, grid, cell(0.0)));
+    }
+
+    #[test]
+    fn fit_columns_accounts_for_spacing() {
+        assert_eq!(fit_columns(100.0, 30.0, 5.0), 3);
+        assert_eq!(fit_columns(99.0, 30.0, 5.0), 2);
+        assert_eq!(fit_columns(20.0, 30.0, 5.0), 0);
+    }
+
+    #[test]
+    fn visual_order_reads_rows_then_columns() {
+        let positions = vec![(1, 0), (0, 1), (0, 0), (1, 1)];
+
+        assert_eq!(visual_order(&positions, false), vec![2, 1, 0, 3]);
+        assert_eq!(visual_order(&positions, true), vec![1, 2, 3, 0]);
+    }
+
+    #[test]
+    fn read_cells_in_order_whatever_their_alignment() {
+        let grid = TestGrid::with_columns(2)
+            .push_cell(stub(10, 10), Cell::new().align_y(Vertical::Bottom))
+            .push(stub(10, 30))
+            .push(stub(10, 10));
+        let mut tree = Tree::new(&grid as &dyn Widget<(), testing::Renderer>);
+        grid.diff(&mut tree);
+
+        let limits = Limits::new(Size::ZERO, Size::new(500.0, 500.0));
+        let node = grid.layout(&testing::Renderer, &limits);
+
+        assert_eq!(node.children()[0].bounds().y, 20.0);
+        assert_eq!(visual_order(&grid.positions(), false), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn navigate_between_cells() {
+        // 3 columns, 3 rows, with the middle cell of the first row missing.
+        let cell = |column: usize, row: usize| {
+            Rectangle::new(
+                Point::new(column as f32 * 10.0, row as f32 * 10.0),
+                Size::new(10.0, 10.0),
+            )
+        };
+        let cells = vec![
+            cell(0, 0),
+            cell(2, 0),
+            cell(0, 1),
+            cell(1, 1),
+            cell(2, 1),
+            cell(0, 2),
+            cell(1, 2),
+            cell(2, 2),
+        ];
+
+        let tests = vec![
+            (3, Movement::Left, Some(2)),
+            (2, Movement::Left, Some(1)),
+            (0, Movement::Left, None),
+            (1, Movement::Right, Some(2)),
+            (7, Movement::Right, None),
+            (3, Movement::Up, Some(0)),
+            (3, Movement::Down, Some(6)),
+            (6, Movement::Down, None),
+            (3, Movement::RowStart, Some(2)),
+            (3, Movement::RowEnd, Some(4)),
+            (3, Movement::First, Some(0)),
+            (3, Movement::Last, Some(7)),
+            (1, Movement::PageDown, Some(7)),
+            (7, Movement::PageUp, Some(1)),
+        ];
+
+        let order: Vec<_> = (0..cells.len()).collect();
+        for (current, movement, expected) in tests {
+            assert_eq!(
+                navigate(&cells, &order, current, movement, 25.0),
+                expected,
+                "{movement:?} from {current}"
+            );
+        }
+    }
+
+    #[test]
+    fn select_click_with_modifiers() {
+        use keyboard::Modifiers;
+
+        let selection = BTreeSet::from([2, 5]);
+        let select = |clicked, modifiers| select_click(&selection, Some(2), clicked, modifiers);
+
+        let tests = vec![
+            (Some(7), Modifiers::empty(), vec![7]),
+            (None, Modifiers::empty(), vec![]),
+            (None, Modifiers::CTRL, vec![2, 5]),
+            (Some(7), Modifiers::CTRL, vec![2, 5, 7]),
+            (Some(5), Modifiers::CTRL, vec![2]),
+            (Some(4), Modifiers::SHIFT, vec![2, 3, 4]),
+            (Some(0), Modifiers::SHIFT, vec![0, 1, 2]),
+            (
+                Some(7),
+                Modifiers::SHIFT | Modifiers::CTRL,
+                vec![2, 3, 4, 5, 6, 7],
+            ),
+        ];
+
+        for (clicked, modifiers, expected) in tests {
+            let selection: Vec<_> = select(clicked, modifiers).into_iter().collect();
+            assert_eq!(selection, expected, "{clicked:?} with {modifiers:?}");
+        }
+    }
+
+    #[test]
+    fn rubber_band_bounds_in_any_direction() {
+        let rubber_band = RubberBand {
+            origin: Point::new(30.0, 10.0),
+            cursor: Point::new(10.0, 40.0),
+            is_dragging: true,
+            base: BTreeSet::new(),
+        };
+
+        assert_eq!(
+            rubber_band.bounds(),
+            Rectangle::new(Point::new(10.0, 10.0), Size::new(20.0, 30.0))
+        );
+    }
+
+    #[test]
+    fn drop_slot_splits_cells_in_half() {
+        let cells = vec![
+            Rectangle::new(Point::new(0.0, 0.0), Size::new(10.0, 10.0)),
+            Rectangle::new(Point::new(10.0, 0.0), Size::new(10.0, 10.0)),
+        ];
+
+        assert_eq!(drop_slot(&cells, Point::new(2.0, 5.0)), Some(0));
+        assert_eq!(drop_slot(&cells, Point::new(8.0, 5.0)), Some(1));
+        assert_eq!(drop_slot(&cells, Point::new(18.0, 5.0)), Some(2));
+        assert_eq!(drop_slot(&cells, Point::new(40.0, 5.0)), Some(2));
+        assert_eq!(drop_slot(&[], Point::new(0.0, 0.0)), None);
+    }
+
+    #[test]
+    fn reorder_to_slot() {
+        assert_eq!(reorder_to(1, 4), Some(Reorder { from: 1, to: 3 }));
+        assert_eq!(reorder_to(3, 0), Some(Reorder { from: 3, to: 0 }));
+        assert_eq!(reorder_to(2, 2), None);
+        assert_eq!(reorder_to(2, 3), None);
+    }
+
+    #[test]
+    fn reorder_apply_moves_item() {
+        let mut items = vec!['a', 'b', 'c', 'd'];
+
+        Reorder { from: 0, to: 2 }.apply(&mut items);
+
+        assert_eq!(items, vec!['b', 'c', 'a', 'd']);
+    }
+
+    #[test]
+    fn reorder_moved_index_follows_apply() {
+        for reorder in [Reorder { from: 0, to: 2 }, Reorder { from: 3, to: 1 }] {
+            let mut items: Vec<_> = (0..5).collect();
+            reorder.apply(&mut items);
+
+            for index in 0..5 {
+                assert_eq!(items[reorder.moved_index(index)], index, "{reorder:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn place_masonry_in_shortest_column() {
+        let heights = vec![30.0, 10.0, 10.0, 20.0, 10.0];
+
+        let (positions, height) = place_masonry(heights.into_iter(), 2, 5.0);
+
+        assert_eq!(
+            positions,
+            vec![(0, 0.0), (1, 0.0), (1, 15.0), (1, 30.0), (0, 35.0)]
+        );
+        assert_eq!(height, 50.0);
+    }
+
+    #[test]
+    fn place_masonry_without_cells() {
+        let (positions, height) = place_masonry(std::iter::empty(), 3, 5.0);
+
+        assert!(positions.is_empty());
+        assert_eq!(height, 0.0);
+    }
+
+    #[test]
+    fn justify_rows_fill_width() {
+        let aspect_ratios = [1.0, 1.0, 2.0, 1.0];
+
+        let rows = justify_rows(&aspect_ratios, 300.0, 100.0, 0.0);
+
+        assert_eq!(
+            rows,
+            vec![
+                JustifiedRow {
+                    cells: 0..3,
+                    height: 75.0
+                },
+                JustifiedRow {
+                    cells: 3..4,
+                    height: 100.0
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn justify_rows_leave_room_for_spacing() {
+        let aspect_ratios = [1.0, 1.0];
+
+        let rows = justify_rows(&aspect_ratios, 210.0, 100.0, 10.0);
+
+        assert_eq!(
+            rows,
+            vec![JustifiedRow {
+                cells: 0..2,
+                height: 100.0
+            }]
+        );
+    }
+
+    #[test]
+    fn place_cells_by_column_fills_columns_first() {
+        let spans = vec![Span::new(1, 1), Span::new(2, 1), Span::new(1, 2)];
+
+        let placements = place_cells_by_column(2, spans.into_iter());
+
+        let positions: Vec<_> = placements.iter().map(|p| (p.row, p.column)).collect();
+        assert_eq!(positions, vec![(0, 0), (0, 1), (0, 2)]);
+        assert_eq!(placements[1].span, Span::new(2, 1));
+        assert_eq!(column_count(&placements), 4);
+    }
+
+    #[test]
+    fn last_row_space_after_partial_row() {
+        let spans = vec![Span::default(); 4];
+        let placements = place_cells(3, spans.into_iter());
+
+        assert_eq!(last_row_space(&placements, &[10.0, 20.0, 30.0], 5.0), 60.0);
+    }
+
+    #[test]
+    fn last_row_space_with_spanning_cell() {
+        let spans = vec![Span::new(2, 1), Span::default(), Span::default()];
+        let placements = place_cells(2, spans.into_iter());
+
+        assert_eq!(last_row_space(&placements, &[10.0, 10.0], 0.0), 0.0);
+    }
+
+    #[test]
+    fn mirror_flips_children() {
+        let mut first = Node::new(Size::new(10.0, 10.0));
+        first.move_to(Point::new(5.0, 0.0));
+        let mut second = Node::new(Size::new(20.0, 10.0));
+        second.move_to(Point::new(20.0, 15.0));
+        let node = Node::with_children(Size::new(50.0, 30.0), vec![first, second]);
+
+        let mirrored = mirror(&node);
+
+        let bounds: Vec<_> = mirrored.children().iter().map(Node::bounds).collect();
+        assert_eq!(
+            bounds,
+            vec![
+                Rectangle::new(Point::new(35.0, 0.0), Size::new(10.0, 10.0)),
+                Rectangle::new(Point::new(10.0, 15.0), Size::new(20.0, 10.0)),
+            ]
+        );
+        assert_eq!(mirrored.size(), node.size());
+    }
+
+    #[test]
+    fn visual_rows_group_overlapping_cells() {
+        let cell = |x, y, height| Rectangle::new(Point::new(x, y), Size::new(10.0, height));
+        let cells = vec![
+            cell(20.0, 0.0, 10.0),
+            cell(0.0, 0.0, 25.0),
+            cell(10.0, 15.0, 10.0),
+            cell(0.0, 30.0, 10.0),
+        ];
+
+        assert_eq!(visual_rows(&cells), vec![vec![1, 2, 0], vec![3]]);
+    }
+
+    #[test]
+    fn dividers_between_rows_and_columns() {
+        let cell = |x, y| Rectangle::new(Point::new(x, y), Size::new(10.0, 10.0));
+        let cells = vec![cell(0.0, 0.0), cell(14.0, 0.0), cell(0.0, 14.0)];
+        let rows = visual_rows(&cells);
+        let content = Rectangle::new(Point::ORIGIN, Size::new(24.0, 24.0));
+
+        assert_eq!(
+            row_dividers(&cells, &rows, content, 2.0),
+            vec![Rectangle::new(Point::new(0.0, 11.0), Size::new(24.0, 2.0))]
+        );
+        assert_eq!(
+            column_dividers(&cells, &rows, 2.0),
+            vec![Rectangle::new(Point::new(11.0, 0.0), Size::new(2.0, 10.0))]
+        );
+    }
+
+    #[test]
+    fn layout_cache_matches_key_and_limits() {
+        let limits = Limits::new(Size::ZERO, Size::new(100.0, 100.0));
+        let other_limits = Limits::new(Size::ZERO, Size::new(50.0, 100.0));
+        let mut cache = LayoutCache::default();
+
+        cache.insert(1, &limits, Node::new(Size::new(10.0, 20.0)));
+
+        assert_eq!(
+            cache.get(1, &limits).map(|node| node.size()),
+            Some(Size::new(10.0, 20.0))
+        );
+        assert!(cache.get(1, &other_limits).is_none());
+        assert!(cache.get(2, &limits).is_none());
+    }
+
+    #[test]
+    fn layout_cache_keeps_recent_layouts() {
+        let limits = |width| Limits::new(Size::ZERO, Size::new(width, 100.0));
+        let mut cache = LayoutCache::default();
+
+        for width in 0..=CACHED_LAYOUTS {
+            #[allow(clippy::cast_precision_loss)]
+            let width = width as f32;
+            cache.insert(1, &limits(width), Node::new(Size::new(width, 10.0)));
+        }
+
+        assert!(cache.get(1, &limits(0.0)).is_none());
+        assert!(cache.get(1, &limits(1.0)).is_some());
+
+        cache.retain(&HashSet::from([2]));
+        assert!(cache.get(1, &limits(1.0)).is_none());
+    }
+
+    #[test]
+    fn tween_eases_to_target() {
+        let started = Instant::now();
+        let duration = Duration::from_millis(100);
+        let tween = Tween {
+            from: Point::new(0.0, 0.0),
+            to: Point::new(80.0, 0.0),
+            started,
+        };
+
+        assert_eq!(tween.position(started, duration), Point::new(0.0, 0.0));
+        assert_eq!(
+            tween.position(started + duration / 2, duration),
+            Point::new(70.0, 0.0)
+        );
+        assert_eq!(
+            tween.position(started + duration * 2, duration),
+            Point::new(80.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn reflow_starts_from_drawn_position() {
+        let started = Instant::now();
+        let duration = Duration::from_millis(100);
+        let id = CellId::Index(0);
+        let mut reflow = Reflow::default();
+
+        let first = reflow.offset(id, Point::new(0.0, 0.0), started, duration);
+        assert_eq!(first, Vector::new(0.0, 0.0));
+
+        let moved = reflow.offset(id, Point::new(80.0, 0.0), started, duration);
+        assert_eq!(moved, Vector::new(-80.0, 0.0));
+
+        let halfway = started + duration / 2;
+        let back = reflow.offset(id, Point::new(0.0, 0.0), halfway, duration);
+        assert_eq!(back, Vector::new(70.0, 0.0));
+
+        let settled = reflow.offset(id, Point::new(0.0, 0.0), halfway + duration, duration);
+        assert_eq!(settled, Vector::new(0.0, 0.0));
+    }
+
+    #[test]
+    fn sticky_offset_within_section() {
+        let header = Rectangle::new(Point::new(0.0, 100.0), Size::new(50.0, 20.0));
+
+        let tests = vec![
+            (50.0, 0.0),
+            (100.0, 0.0),
+            (150.0, 50.0),
+            (190.0, 80.0),
+            (400.0, 80.0),
+        ];
+
+        for (top, expected) in tests {
+            assert_eq!(sticky_offset(header, 200.0, top), expected, "at {top}");
+        }
+    }
+
+    #[test]
+    fn place_cells_puts_headers_on_their_own_row() {
+        let spans = vec![
+            Cell::new().header().span,
+            Span::default(),
+            Span::default(),
+            Span::default(),
+            Cell::new().header().span,
+            Span::default(),
+        ];
+
+        let placements = place_cells(2, spans.into_iter());
+
+        let positions: Vec<_> = placements.iter().map(|p| (p.row, p.column)).collect();
+        assert_eq!(
+            positions,
+            vec![(0, 0), (1, 0), (1, 1), (2, 0), (3, 0), (4, 0)]
+        );
+        assert_eq!(placements[0].span, Span::new(1, 2));
+    }
+
+    #[test]
+    fn place_cells_by_column_puts_headers_on_their_own_column() {
+        let spans = vec![
+            Cell::new().header().span,
+            Span::default(),
+            Span::default(),
+            Span::default(),
+        ];
+
+        let placements = place_cells_by_column(2, spans.into_iter());
+
+        let positions: Vec<_> = placements.iter().map(|p| (p.row, p.column)).collect();
+        assert_eq!(positions, vec![(0, 0), (0, 1), (1, 1), (0, 2)]);
+        assert_eq!(placements[0].span, Span::new(2, 1));
+    }
+
+    #[test]
+    fn layout_fixed_rows_fills_every_row() {
+        let grid = (0..5).fold(TestGrid::with_rows(4).cell_size(10, 10), |grid, _| {
+            grid.push(stub(10, 10))
+        });
+
+        let node = testing::layout(grid, Size::new(500.0, 500.0));
+
+        assert_cells(
+            &node,
+            &[
+                rect(0.0, 0.0, 10.0, 10.0),
+                rect(0.0, 10.0, 10.0, 10.0),
+                rect(0.0, 20.0, 10.0, 10.0),
+                rect(0.0, 30.0, 10.0, 10.0),
+                rect(10.0, 0.0, 10.0, 10.0),
+            ],
+        );
+        assert_size(&node, Size::new(20.0, 40.0));
+    }
+
+    #[test]
+    fn visible_cells_of_fixed_rows() {
+        let grid = (0..5)
+            .fold(TestGrid::with_rows(4).cell_size(10, 10), |grid, _| {
+                grid.push(stub(10, 10))
+            })
+            .viewport(rect(10.0, 0.0, 10.0, 40.0));
+
+        let visible = grid.visible_cells(2, Size::new(10.0, 10.0));
+        assert_eq!(visible.indices(5).collect::<Vec<_>>(), vec![4]);
+    }
+
+    #[test]
+    fn visible_cells_of_fixed_rows_right_to_left() {
+        let grid = |x| {
+            (0..10)
+                .fold(TestGrid::with_rows(2).cell_size(10, 10), |grid, _| {
+                    grid.push(stub(10, 10))
+                })
+                .direction(Direction::RightToLeft)
+                .viewport(rect(x, 0.0, 10.0, 20.0))
+        };
+        let visible = |x| {
+            let visible = grid(x).visible_cells(5, Size::new(10.0, 10.0));
+            visible.indices(10).collect::<Vec<_>>()
+        };
+
+        // the first column is on the right
+        assert_eq!(visible(0.0), vec![8, 9]);
+        assert_eq!(visible(40.0), vec![0, 1]);
+    }
+
+    #[test]
+    fn visible_cells_of_wide_grid() {
+        let grid = (0..100)
+            .fold(TestGrid::with_columns(10).cell_size(10, 10), |grid, _| {
+                grid.push(stub(10, 10))
+            })
+            .viewport(rect(20.0, 10.0, 20.0, 20.0));
+
+        let visible = grid.visible_cells(10, Size::new(10.0, 10.0));
+        assert_eq!(
+            visible.indices(100).collect::<Vec<_>>(),
+            vec![12, 13, 22, 23]
+        );
+    }
+
+    #[test]
+    fn built_block_maps_slots_to_cells() {
+        let built = Built::block(1..3, 2..4, 10);
+
+        assert_eq!(
+            (0..4).map(|slot| built.index(slot)).collect::<Vec<_>>(),
+            vec![12, 13, 22, 23]
+        );
+        assert_eq!(built.slot(22), Some(2));
+        assert_eq!(built.slot(14), None);
+        assert_eq!(built.slot(32), None);
+        assert_eq!(Built::default().slot(7), Some(7));
+    }
+
+    #[test]
+    fn diff_keeps_cell_state_with_its_index() {
+        let grid = |top| {
+            TestGrid::with_view(10, |_| TestGrid::with_columns(1).into())
+                .columns(1)
+                .cell_size(10, 10)
+                .viewport(rect(0.0, top, 10.0, 30.0))
+                .build_in_view()
+        };
+        let is_focused = |tree: &Tree| tree.state.downcast_ref::<State>().is_focused;
+
+        let first = grid(0.0);
+        let mut tree = Tree::new(&first as &dyn Widget<(), testing::Renderer>);
+        tree.children[1].state.downcast_mut::<State>().is_focused = true;
+
+        grid(10.0).diff(&mut tree);
+
+        let focused: Vec<_> = tree.children.iter().map(is_focused).collect();
+        assert_eq!(focused, vec![true, false, false]);
+    }
+
+    #[test]
+    fn layout_fill_stretches_element_to_cell() {
+        let grid = TestGrid::with_columns(2)
+            .push_cell(stub(10, 10), Cell::new().fill())
+            .push(stub(30, 20))
+            .push(stub(20, 5))
+            .push(stub(10, 10));
+
+        let node = testing::layout(grid, Size::new(500.0, 500.0));
+
+        assert_cell_bounds(&node, 0, rect(0.0, 0.0, 20.0, 20.0));
+        assert_cell_bounds(&node, 2, rect(0.0, 20.0, 20.0, 5.0));
+    }
+
+    #[test]
+    fn layout_rows_with_header() {
+        let grid = TestGrid::with_rows(2)
+            .push_header(stub(10, 30))
+            .push(stub(20, 10))
+            .push(stub(20, 10))
+            .push(stub(20, 10));
+
+        let node = testing::layout(grid, Size::new(500.0, 500.0));
+
+        assert_size(&node, Size::new(50.0, 30.0));
+    }
+
+    #[test]
+    fn double_click_on_same_cell_in_time() {
+        let now = Instant::now();
+        let later = now + Duration::from_millis(200);
+        let too_late = now + DOUBLE_CLICK_INTERVAL + Duration::from_millis(1);
+
+        assert!(is_double_click(Some((3, now)), 3, later));
+        assert!(!is_double_click(Some((3, now)), 4, later));
+        assert!(!is_double_click(Some((3, now)), 3, too_late));
+        assert!(!is_double_click(None, 3, later));
+    }
+
+    #[test]
+    fn layout_columns_fit_their_widest_cell() {
+        let grid = TestGrid::with_columns(2)
+            .spacing(10)
+            .push(stub(10, 20))
+            .push(stub(30, 10))
+            .push(stub(20, 5));
+
+        let node = testing::layout(grid, Size::new(500.0, 500.0));
+
+        assert_cells(
+            &node,
+            &[
+                rect(0.0, 0.0, 10.0, 20.0),
+                rect(30.0, 0.0, 30.0, 10.0),
+                rect(0.0, 30.0, 20.0, 5.0),
+            ],
+        );
+        assert_size(&node, Size::new(60.0, 35.0));
+    }
+
+    #[test]
+    fn layout_column_width_fits_columns_in_window() {
+        let grid = (0..5).fold(TestGrid::with_column_width(50).spacing(10), |grid, _| {
+            grid.push(stub(20, 20))
+        });
+
+        let node = testing::layout(grid, Size::new(180.0, 500.0));
+
+        assert_cell_bounds(&node, 1, rect(60.0, 0.0, 50.0, 20.0));
+        assert_cell_bounds(&node, 2, rect(120.0, 0.0, 50.0, 20.0));
+        assert_cell_bounds(&node, 3, rect(0.0, 30.0, 50.0, 20.0));
+    }
+
+    #[test]
+    fn layout_tracks_share_remaining_width() {
+        let grid = TestGrid::with_tracks(
+            vec![Track::Fixed(50), Track::Fill, Track::FillPortion(2)],
+            Vec::new(),
+        )
+        .push(Stub::new(Length::Fill, 10))
+        .push(Stub::new(Length::Fill, 10))
+        .push(Stub::new(Length::Fill, 10));
+
+        let node = testing::layout(grid, Size::new(350.0, 100.0));
+
+        assert_cells(
+            &node,
+            &[
+                rect(0.0, 0.0, 50.0, 10.0),
+                rect(50.0, 0.0, 100.0, 10.0),
+                rect(150.0, 0.0, 200.0, 10.0),
+            ],
+        );
+    }
+
+    #[test]
+    fn layout_spanning_cell_and_padding() {
+        let grid = TestGrid::with_columns(2)
+            .padding(5)
+            .push_spanning(stub(10, 30), 2, 1)
+            .push(stub(20, 10))
+            .push(stub(20, 10));
+
+        let node = testing::layout(grid, Size::new(500.0, 500.0));
+
+        assert_cells(
+            &node,
+            &[
+                rect(5.0, 5.0, 10.0, 30.0),
+                rect(15.0, 5.0, 20.0, 10.0),
+                rect(15.0, 20.0, 20.0, 10.0),
+            ],
+        );
+        assert_size(&node, Size::new(40.0, 40.0));
+    }
+
+    #[test]
+    fn layout_right_to_left_mirrors_cells() {
+        let grid = TestGrid::with_columns(2)
+            .direction(Direction::RightToLeft)
+            .push(stub(10, 10))
+            .push(stub(20, 10));
+
+        let node = testing::layout(grid, Size::new(500.0, 500.0));
+
+        assert_cells(
+            &node,
+            &[rect(20.0, 0.0, 10.0, 10.0), rect(0.0, 0.0, 20.0, 10.0)],
+        );
+    }
+
+    #[test]
+    fn layout_measures_text() {
+        let grid = TestGrid::with_columns(2)
+            .push(iced_native::widget::Text::new("abcd").size(20))
+            .push(stub(10, 10));
+
+        let node = testing::layout(grid, Size::new(500.0, 500.0));
+
+        assert_cells(
+            &node,
+            &[rect(0.0, 0.0, 40.0, 20.0), rect(40.0, 0.0, 10.0, 10.0)],
+        );
     }
 }
@@ -0,0 +1,145 @@
+//! The appearance of the cells of a [`Grid`](super::Grid) and of the lines
+//! between them.
+use std::rc::Rc;
+
+use iced::{Background, Color, Theme};
+
+/// The appearance of a cell of a [`Grid`](super::Grid).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Appearance {
+    /// The background of the cell.
+    pub background: Option<Background>,
+    /// The radius of the corners of the cell.
+    pub border_radius: f32,
+    /// The width of the border of the cell.
+    pub border_width: f32,
+    /// The color of the border of the cell.
+    pub border_color: Color,
+}
+
+/// A line drawn between the rows or columns of a [`Grid`](super::Grid).
+#[derive(Debug, Clone, Copy)]
+pub struct Divider {
+    /// The thickness of the line.
+    pub width: f32,
+    /// The color of the line.
+    pub color: Color,
+}
+
+/// The appearance of a [`Grid`](super::Grid).
+pub trait StyleSheet {
+    /// The supported style of the [`StyleSheet`](StyleSheet).
+    type Style: Default;
+
+    /// The appearance of a cell that is neither hovered nor selected.
+    fn appearance(&self, style: &Self::Style) -> Appearance;
+
+    /// The appearance of the cell under the mouse cursor.
+    fn hovered(&self, style: &Self::Style) -> Appearance {
+        self.appearance(style)
+    }
+
+    /// The appearance of a selected cell.
+    fn selected(&self, style: &Self::Style) -> Appearance;
+
+    /// The appearance of the row of a section header.
+    fn header(&self, style: &Self::Style) -> Appearance {
+        self.appearance(style)
+    }
+
+    /// The line drawn between two rows, if any.
+    fn row_divider(&self, _style: &Self::Style) -> Option<Divider> {
+        None
+    }
+
+    /// The line drawn between two columns, if any.
+    fn column_divider(&self, _style: &Self::Style) -> Option<Divider> {
+        None
+    }
+}
+
+/// The styles of a [`Grid`](super::Grid) for the built-in [`Theme`](Theme).
+#[derive(Clone, Default)]
+#[allow(missing_debug_implementations)]
+pub enum GridStyles {
+    /// Highlights hovered and selected cells.
+    #[default]
+    Default,
+    /// Like [`Default`](GridStyles::Default), with lines between the rows and
+    /// columns.
+    Lined,
+    /// A custom style.
+    Custom(Rc<dyn StyleSheet<Style = Theme>>),
+}
+
+impl StyleSheet for Theme {
+    type Style = GridStyles;
+
+    fn appearance(&self, style: &Self::Style) -> Appearance {
+        match style {
+            GridStyles::Default | GridStyles::Lined => Appearance::default(),
+            GridStyles::Custom(custom) => custom.appearance(self),
+        }
+    }
+
+    fn hovered(&self, style: &Self::Style) -> Appearance {
+        let palette = self.extended_palette();
+
+        match style {
+            GridStyles::Default | GridStyles::Lined => Appearance {
+                background: Some(Background::Color(palette.background.weak.color)),
+                ..Appearance::default()
+            },
+            GridStyles::Custom(custom) => custom.hovered(self),
+        }
+    }
+
+    fn selected(&self, style: &Self::Style) -> Appearance {
+        let palette = self.extended_palette();
+
+        match style {
+            GridStyles::Default | GridStyles::Lined => Appearance {
+                background: Some(Background::Color(Color {
+                    a: 0.2,
+                    ..palette.primary.base.color
+                })),
+                border_radius: 0.0,
+                border_width: 1.0,
+                border_color: palette.primary.strong.color,
+            },
+            GridStyles::Custom(custom) => custom.selected(self),
+        }
+    }
+
+    fn header(&self, style: &Self::Style) -> Appearance {
+        match style {
+            GridStyles::Default | GridStyles::Lined => Appearance {
+                background: Some(Background::Color(self.palette().background)),
+                ..Appearance::default()
+            },
+            GridStyles::Custom(custom) => custom.header(self),
+        }
+    }
+
+    fn row_divider(&self, style: &Self::Style) -> Option<Divider> {
+        match style {
+            GridStyles::Default => None,
+            GridStyles::Lined => Some(Divider {
+                width: 1.0,
+                color: self.extended_palette().background.strong.color,
+            }),
+            GridStyles::Custom(custom) => custom.row_divider(self),
+        }
+    }
+
+    fn column_divider(&self, style: &Self::Style) -> Option<Divider> {
+        match style {
+            GridStyles::Default => None,
+            GridStyles::Lined => Some(Divider {
+                width: 1.0,
+                color: self.extended_palette().background.strong.color,
+            }),
+            GridStyles::Custom(custom) => custom.column_divider(self),
+        }
+    }
+}
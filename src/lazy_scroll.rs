@@ -1,44 +1,164 @@
-// use std::cell::Cell;
+mod source;
+mod viewport;
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::BTreeMap,
+    ops::Range,
+    rc::Rc,
+};
 
 use iced::{
-    widget::{column, container, scrollable, text},
-    Command, Element, Length, Size,
+    widget::{button, column, container, radio, row, scrollable, text, text_input},
+    Command, Element, Length, Renderer, Size, Vector,
+};
+use iced_lazy::{responsive, Component};
+use iced_native::{
+    event,
+    layout::{Limits, Node},
+    mouse, renderer,
+    widget::{operation, Operation, Tree, Widget},
+    Clipboard, Event as NativeEvent, Layout, Point, Rectangle, Shell,
 };
-use iced_lazy::responsive;
 
-use crate::{grid::Grid, Tab};
+use crate::{
+    grid::{style::GridStyles, Grid},
+    Tab,
+};
+
+use source::{FetchError, JsonLinesSource, MemorySource, Page, Paged};
+use viewport::VisibleTiles;
+pub use viewport::{Align, TileLayout, Viewport};
 
 const DEFAULT_TILE_SIZE: u16 = 200;
+const TILE_COUNT: usize = 1_000_000;
+const NOTE_COUNT: usize = 100_000;
+
+/// How many tiles the "Insert above" button inserts before the others.
+const INSERTED_TILES: usize = 10;
 
-#[derive(Debug, Clone, Copy)]
+/// How many tiles are fetched at once.
+const PAGE_SIZE: usize = 100;
+
+/// The name of the file in the working directory with a tile on every line,
+/// shown instead of generated tiles if it exists.
+const TILES_FILE: &str = "lazy_scroll.jsonl";
+
+/// How many rows are built above and below the ones in view by default.
+const DEFAULT_OVERSCAN: usize = 2;
+
+/// The height assumed for the items of a [`VirtualList`](VirtualList) until
+/// some are measured.
+const DEFAULT_ESTIMATED_HEIGHT: f32 = 40.0;
+
+#[derive(Debug, Clone)]
 pub enum Message {
-    Scrolled(scrollable::RelativeOffset),
+    Visibility(VisibilityChange),
+    PageFetched(usize, Result<Page<String>, FetchError>),
+    JumpInputChanged(String),
+    AlignSelected(Align),
+    JumpSubmitted,
+    Jumped,
+    InsertAbove,
 }
 
 pub struct LazyScroll {
-    elements: Vec<String>,
-    current_offset: scrollable::RelativeOffset,
-    // size: Cell<Size>,
+    tiles: Paged<String>,
+    visible: Range<usize>,
+    jump_input: String,
+    align: Align,
+    /// The tile to jump to, until the grid has scrolled to it.
+    jump: Option<(usize, Align)>,
+    /// How many tiles were inserted before the ones of the source.
+    inserted: usize,
 }
 
 impl LazyScroll {
     pub fn new() -> Self {
+        let mut path = std::env::current_dir().unwrap_or_default();
+        path.push(TILES_FILE);
+
+        let tiles = if path.is_file() {
+            Paged::new(JsonLinesSource::new(path, PAGE_SIZE))
+        } else {
+            // streamed, so more tiles are fetched as the end is reached
+            let tiles = (0..TILE_COUNT).map(|i| format!("Tile-{i}")).collect();
+            Paged::new(MemorySource::new(tiles, PAGE_SIZE).unknown_length())
+        };
+
         Self {
-            elements: (0..100).map(|i| format!("Placeholder-{i}")).collect(),
-            current_offset: scrollable::RelativeOffset::START,
-            // size: Cell::new(Size::UNIT),
+            tiles,
+            visible: 0..0,
+            jump_input: String::new(),
+            align: Align::Start,
+            jump: None,
+            inserted: 0,
         }
     }
 
+    fn len(&self) -> usize {
+        self.inserted + self.tiles.len()
+    }
+
+    /// Returns the key of the tile at `index`.
+    ///
+    /// Tiles of the source are keyed by their index in it, and inserted ones
+    /// count down from `u64::MAX` in the order they were inserted.
+    fn key(&self, index: usize) -> u64 {
+        let key = |index: usize| u64::try_from(index).unwrap_or(u64::MAX);
+
+        match index.checked_sub(self.inserted) {
+            Some(index) => key(index),
+            None => u64::MAX - key(self.inserted - 1 - index),
+        }
+    }
+
+    /// Returns the index of the tile with the given `key`.
+    fn position(&self, key: u64) -> Option<usize> {
+        let index = |key: u64| usize::try_from(key).ok();
+
+        let index = match index(u64::MAX - key) {
+            Some(nth) if nth < self.inserted => self.inserted - 1 - nth,
+            _ => index(key)?.checked_add(self.inserted)?,
+        };
+        (index < self.len()).then_some(index)
+    }
+
     pub fn update(&mut self, message: Message) -> Command<Message> {
         match message {
-            Message::Scrolled(offset) => {
-                self.current_offset = offset;
+            Message::Visibility(VisibilityChange::VisibleRangeChanged { start, end }) => {
+                self.visible = start..end;
+            }
+            Message::Visibility(VisibilityChange::ItemsEntered(tiles)) => {
+                let inserted = self.inserted;
+                let pages = self
+                    .tiles
+                    .missing_pages(tiles.into_iter().filter_map(|i| i.checked_sub(inserted)));
 
-                // visible_elements();
-                Command::none()
+                return Command::batch(pages.into_iter().map(|page| {
+                    Command::perform(self.tiles.fetch(page), move |result| {
+                        Message::PageFetched(page, result)
+                    })
+                }));
             }
+            Message::Visibility(VisibilityChange::ItemsLeft(_)) => {}
+            Message::PageFetched(page, result) => self.tiles.insert(page, result),
+            Message::JumpInputChanged(input) => {
+                if input.chars().all(|c| c.is_ascii_digit()) {
+                    self.jump_input = input;
+                }
+            }
+            Message::AlignSelected(align) => self.align = align,
+            Message::JumpSubmitted => {
+                if let Ok(index) = self.jump_input.parse::<usize>() {
+                    self.jump = Some((index.min(self.len().saturating_sub(1)), self.align));
+                }
+            }
+            Message::Jumped => self.jump = None,
+            Message::InsertAbove => self.inserted += INSERTED_TILES,
         }
+
+        Command::none()
     }
 }
 
@@ -54,48 +174,76 @@ impl Tab for LazyScroll {
     }
 
     fn content(&self) -> Element<'_, Self::Message> {
-        let content = |size: Size| {
-            let n_columns = size.width as usize / DEFAULT_TILE_SIZE as usize;
-            let visible = visible_tiles(
-                n_columns,
-                self.elements.len(),
-                DEFAULT_TILE_SIZE,
-                size,
-                self.current_offset,
-            );
+        let mut tiles = VirtualGrid::new(self.len(), |i| {
+            let content = match i.checked_sub(self.inserted) {
+                Some(i) => match (self.tiles.get(i), self.tiles.error(i)) {
+                    (Some(tile), _) => text(tile),
+                    (None, Some(FetchError::File)) => text("Could not read the tiles"),
+                    (None, Some(FetchError::Format)) => text("Invalid tile"),
+                    (None, None) => text("Loading…"),
+                },
+                None => text(format!("New-{}", self.inserted - 1 - i)),
+            };
 
-            // println!("\n update: visible: {visible:?}");
-
-            scrollable(
-                column!(Grid::with_children(
-                    self.elements
-                        .iter()
-                        .enumerate()
-                        .map(|(i, s)| {
-                            container(
-                                text(format!(
-                                    "{}: {}",
-                                    s,
-                                    if visible.contains(i) { "vis" } else { "hid" }
-                                ))
-                                .height(Length::Units(DEFAULT_TILE_SIZE))
-                                .width(Length::Units(DEFAULT_TILE_SIZE))
-                                .vertical_alignment(iced::alignment::Vertical::Center),
-                            )
-                            .style(text_style::Text)
-                            .into()
-                        })
-                        .collect()
-                )
-                .columns(n_columns))
-                .width(Length::Fill),
-            )
-            .vertical_scroll(scrollable::Properties::new())
-            .on_scroll(Message::Scrolled)
-            .into()
+            content
+                .height(Length::Units(DEFAULT_TILE_SIZE))
+                .width(Length::Units(DEFAULT_TILE_SIZE))
+                .vertical_alignment(iced::alignment::Vertical::Center)
+                .into()
+        })
+        .tile_size(DEFAULT_TILE_SIZE)
+        .style(GridStyles::Lined)
+        .on_visibility_change(Message::Visibility)
+        .on_jump(|_| Message::Jumped)
+        .anchored(|i| self.key(i), |key| self.position(key));
+        if let Some((index, align)) = self.jump {
+            tiles = tiles.scroll_to(index, align);
+        }
+
+        let notes = VirtualList::new(NOTE_COUNT, |i| {
+            let words = "Lorem ipsum dolor sit amet, consectetur adipiscing elit. ";
+            text(format!("Note {i}: {}", words.repeat(i % 7 + 1)))
+                .width(Length::Fill)
+                .into()
+        })
+        .spacing(10);
+
+        let len = if self.tiles.is_infinite() {
+            format!("{}+", self.len())
+        } else {
+            self.len().to_string()
         };
+        let position = text(format!(
+            "Tiles {}-{} of {len} in view, {} pages loaded",
+            self.visible.start,
+            self.visible.end,
+            self.tiles.fetched_pages()
+        ));
+
+        let input = text_input(
+            "Jump to tile #",
+            &self.jump_input,
+            Message::JumpInputChanged,
+        )
+        .on_submit(Message::JumpSubmitted)
+        .padding(5)
+        .width(Length::Units(200));
+        let jump = [Align::Start, Align::Center, Align::End, Align::Nearest]
+            .into_iter()
+            .fold(row!(input), |jump, align| {
+                let label = format!("{align:?}");
+                jump.push(radio(
+                    label,
+                    align,
+                    Some(self.align),
+                    Message::AlignSelected,
+                ))
+            })
+            .push(button("Insert above").on_press(Message::InsertAbove))
+            .spacing(20)
+            .align_items(iced::Alignment::Center);
 
-        container(responsive(content))
+        container(column!(jump, row!(tiles, notes).spacing(20), position).spacing(10))
             .width(Length::Fill)
             .height(Length::Fill)
             .padding(40)
@@ -105,113 +253,1316 @@ impl Tab for LazyScroll {
     }
 }
 
-// inclusive bounded range.
+/// A scrollable grid of `len` tiles of the same size, which only builds the
+/// rows in view and a few around them.
+///
+/// The tiles are laid out by a [`Grid`](Grid) of the same size as if every
+/// tile was there, so the scrollbar behaves as if they were, but which is
+/// only given the tiles in view to build.
+#[allow(missing_debug_implementations)]
+pub struct VirtualGrid<'a, Message> {
+    /// The number of tiles.
+    len: usize,
+    /// Builds the tile at an index.
+    view: Box<dyn Fn(usize) -> Element<'a, Message> + 'a>,
+    /// The width and height of a tile.
+    tile_size: u16,
+    /// The space between two tiles.
+    spacing: u16,
+    /// How many rows are built above and below the ones in view.
+    overscan: usize,
+    /// The message produced when the grid is scrolled.
+    on_scroll: Option<Box<dyn Fn(scrollable::RelativeOffset) -> Message + 'a>>,
+    /// The message produced when tiles come into view or leave it.
+    on_visibility_change: Option<Box<dyn Fn(VisibilityChange) -> Message + 'a>>,
+    /// The tile to scroll to, and where to show it.
+    scroll_to: Option<(usize, Align)>,
+    /// The message produced once the grid has scrolled to a tile.
+    on_jump: Option<Box<dyn Fn(usize) -> Message + 'a>>,
+    /// Finds the first tile in view again after tiles are inserted or
+    /// removed.
+    anchoring: Option<Anchoring<'a>>,
+    /// How the tiles fill the rows.
+    flow: Flow,
+    /// The style of the grid of tiles.
+    style: GridStyles,
+}
+
+/// How the tiles of a [`VirtualGrid`](VirtualGrid) fill its rows.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-struct BoundedRange {
-    start: usize,
-    end: usize,
+enum Flow {
+    /// As many columns as fit in the width of the grid, scrolled vertically.
+    Wrap,
+    /// A single row, scrolled horizontally.
+    Row,
+    /// A number of columns, scrolled both ways.
+    Columns(usize),
+}
+
+/// How a [`VirtualGrid`](VirtualGrid) tells its tiles apart.
+struct Anchoring<'a> {
+    /// Returns the key of the tile at an index.
+    key: Box<dyn Fn(usize) -> u64 + 'a>,
+    /// Returns the index of the tile with a key, if there is one.
+    position: Box<dyn Fn(u64) -> Option<usize> + 'a>,
+}
+
+/// A change of the tiles of a [`VirtualGrid`](VirtualGrid) in view.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VisibilityChange {
+    /// The tiles from `start` to `end`, excluded, are now the ones in view.
+    VisibleRangeChanged { start: usize, end: usize },
+    /// The tiles came within the overscan margin of the view, so they are
+    /// about to be shown.
+    ItemsEntered(Vec<usize>),
+    /// The tiles are no longer within the overscan margin of the view.
+    ItemsLeft(Vec<usize>),
 }
 
-impl BoundedRange {
-    fn contains(&self, value: usize) -> bool {
-        (self.start <= value) && (value <= self.end)
+impl<'a, Message> VirtualGrid<'a, Message> {
+    /// Creates a new [`VirtualGrid`](VirtualGrid) of `len` tiles, building
+    /// the tiles in view with `view`.
+    pub fn new<F>(len: usize, view: F) -> Self
+    where
+        F: Fn(usize) -> Element<'a, Message> + 'a,
+    {
+        Self {
+            len,
+            view: Box::new(view),
+            tile_size: DEFAULT_TILE_SIZE,
+            spacing: 0,
+            overscan: DEFAULT_OVERSCAN,
+            on_scroll: None,
+            on_visibility_change: None,
+            scroll_to: None,
+            on_jump: None,
+            anchoring: None,
+            flow: Flow::Wrap,
+            style: GridStyles::default(),
+        }
+    }
+
+    /// Sets the width and height of a tile.
+    pub fn tile_size(self, tile_size: u16) -> Self {
+        Self { tile_size, ..self }
+    }
+
+    /// Sets the space between two tiles.
+    pub fn spacing(self, spacing: u16) -> Self {
+        Self { spacing, ..self }
+    }
+
+    /// Lays out the tiles in a single row scrolled horizontally, like a
+    /// filmstrip.
+    pub fn filmstrip(self) -> Self {
+        Self {
+            flow: Flow::Row,
+            ..self
+        }
+    }
+
+    /// Lays out the tiles in `columns` columns, however wide the grid is, and
+    /// scrolls horizontally as well when they are wider.
+    pub fn columns(self, columns: usize) -> Self {
+        Self {
+            flow: Flow::Columns(columns),
+            ..self
+        }
+    }
+
+    /// Sets the style of the grid of tiles.
+    pub fn style(self, style: impl Into<GridStyles>) -> Self {
+        Self {
+            style: style.into(),
+            ..self
+        }
+    }
+
+    /// Sets how many rows, and columns unless the columns fit in the grid,
+    /// are built around the ones in view, so they are ready before they are
+    /// scrolled into view.
+    pub fn overscan(self, overscan: usize) -> Self {
+        Self { overscan, ..self }
+    }
+
+    /// Sets the message produced with the new offset when the grid is
+    /// scrolled.
+    pub fn on_scroll<F>(self, on_scroll: F) -> Self
+    where
+        F: Fn(scrollable::RelativeOffset) -> Message + 'a,
+    {
+        Self {
+            on_scroll: Some(Box::new(on_scroll)),
+            ..self
+        }
+    }
+
+    /// Sets the message produced for every [`VisibilityChange`](VisibilityChange)
+    /// of the tiles.
+    ///
+    /// Changes are reported with the event following the scroll, and tiles
+    /// enter once they are within the [`overscan`](Self::overscan) margin, so
+    /// their content can be loaded before they are shown.
+    pub fn on_visibility_change<F>(self, on_visibility_change: F) -> Self
+    where
+        F: Fn(VisibilityChange) -> Message + 'a,
+    {
+        Self {
+            on_visibility_change: Some(Box::new(on_visibility_change)),
+            ..self
+        }
+    }
+
+    /// Scrolls to the tile at `index`, showing it where `align` says.
+    ///
+    /// The grid scrolls once for a given tile and alignment, and is then left
+    /// where the user scrolls it. To scroll to the same tile again later,
+    /// stop asking once the grid has scrolled, as told by
+    /// [`on_jump`](Self::on_jump).
+    pub fn scroll_to(self, index: usize, align: Align) -> Self {
+        Self {
+            scroll_to: Some((index, align)),
+            ..self
+        }
+    }
+
+    /// Sets the message produced with the index of the tile once the grid
+    /// has scrolled to it.
+    pub fn on_jump<F>(self, on_jump: F) -> Self
+    where
+        F: Fn(usize) -> Message + 'a,
+    {
+        Self {
+            on_jump: Some(Box::new(on_jump)),
+            ..self
+        }
+    }
+
+    /// Keeps the first tile in view in place when tiles are inserted or
+    /// removed above it.
+    ///
+    /// The tile is told apart by its `key`, and found again by the `position`
+    /// of its key, so scrolling stays put while the tiles change.
+    pub fn anchored<K, P>(self, key: K, position: P) -> Self
+    where
+        K: Fn(usize) -> u64 + 'a,
+        P: Fn(u64) -> Option<usize> + 'a,
+    {
+        Self {
+            anchoring: Some(Anchoring {
+                key: Box::new(key),
+                position: Box::new(position),
+            }),
+            ..self
+        }
+    }
+
+    /// Returns the offset that brings the tile of the `state`'s anchor back
+    /// to where it was in the `viewport`, if the tiles changed since.
+    fn reanchor(
+        &self,
+        state: &State,
+        layout: &TileLayout,
+        columns: usize,
+        viewport: Viewport,
+    ) -> Option<scrollable::RelativeOffset> {
+        let anchoring = self.anchoring.as_ref()?;
+        let anchor = state.anchor?;
+
+        let index = if anchor.index < self.len && (anchoring.key)(anchor.index) == anchor.key {
+            anchor.index
+        } else {
+            (anchoring.position)(anchor.key).filter(|&index| index < self.len)?
+        };
+
+        layout.reanchor(columns, viewport, index, anchor.offset)
+    }
+
+    /// Returns the anchor of the first tile in the `viewport`.
+    fn anchor(&self, layout: &TileLayout, columns: usize, viewport: Viewport) -> Option<Anchor> {
+        let anchoring = self.anchoring.as_ref()?;
+        let (index, offset) = layout.anchor(columns, viewport)?;
+
+        Some(Anchor {
+            key: (anchoring.key)(index),
+            index,
+            offset,
+        })
+    }
+
+    /// Returns how the tiles are laid out.
+    fn tile_layout(&self) -> TileLayout {
+        let tile = f32::from(self.tile_size);
+
+        TileLayout::new(self.len, Size::new(tile, tile)).spacing(f32::from(self.spacing))
+    }
+
+    /// Builds the tiles in view of a scrollable of `size`.
+    fn view_tiles(&self, state: &State, size: Size) -> Element<'_, Event<Message>>
+    where
+        Message: 'static,
+    {
+        let layout = self.tile_layout();
+        let columns = match self.flow {
+            // a window too narrow for a single tile cuts it instead of hiding it
+            Flow::Wrap => layout.columns(size.width).max(1),
+            Flow::Row => self.len.max(1),
+            Flow::Columns(columns) => columns.max(1),
+        };
+        let content_size = layout.content_size(columns);
+        let scrolled = Viewport::from_relative(state.offset, size, content_size);
+        let snap = self
+            .scroll_to
+            .filter(|&target| state.jumped != Some(target))
+            .map(|target @ (index, align)| Snap {
+                offset: layout.offset_of(index, columns, scrolled, align),
+                jumped: Some(target),
+            })
+            .or_else(|| {
+                self.reanchor(state, &layout, columns, scrolled)
+                    .map(|offset| Snap {
+                        offset,
+                        jumped: None,
+                    })
+            });
+        // the tiles are built where the scrollable is about to be snapped
+        let viewport = snap.map_or(scrolled, |snap| {
+            Viewport::from_relative(snap.offset, size, content_size)
+        });
+        let in_view = layout.visible(columns, viewport);
+        let anchor = self
+            .anchor(&layout, columns, viewport)
+            .filter(|&anchor| state.anchor != Some(anchor));
+        let column_overscan = if self.flow == Flow::Wrap {
+            0
+        } else {
+            self.overscan
+        };
+        let visible = in_view
+            .clone()
+            .with_overscan(self.overscan, column_overscan);
+
+        // the grid only builds the tiles in the viewport it is given
+        let grid = Grid::with_view(self.len, |index| (self.view)(index).map(Event::Tile))
+            .columns(columns)
+            .cell_size(self.tile_size, self.tile_size)
+            .spacing(self.spacing)
+            .viewport(layout.bounds(&visible))
+            .style(self.style.clone());
+
+        let content: Element<'_, Event<Message>> = if self.flow == Flow::Wrap {
+            scrollable(container(grid).width(Length::Fill))
+        } else {
+            scrollable(grid).horizontal_scroll(scrollable::Properties::new())
+        }
+        .height(Length::Fill)
+        .vertical_scroll(scrollable::Properties::new())
+        .on_scroll(Event::Scrolled)
+        .id(state.id.clone())
+        .into();
+
+        let ranges = Ranges {
+            visible: in_view.range(),
+            built: visible,
+        };
+
+        // always wrapped, so the state of the scrollable is kept
+        Report {
+            content,
+            changes: visibility_changes(&state.reported, &ranges),
+            ranges,
+            id: state.id.clone(),
+            snap,
+            anchor,
+        }
+        .into()
     }
 }
 
-fn widget_height_in_rows(element_height: u16, widget_size: Size) -> f32 {
-    widget_size.height / element_height as f32
+/// The tiles of a [`VirtualGrid`](VirtualGrid) in view, and the ones built
+/// around them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Ranges {
+    /// The rows in view, from their first tile to their last.
+    visible: Range<usize>,
+    built: VisibleTiles,
 }
 
-// offset is 0.0 top of row[0] and 1.0 is top of row[n-rows_in_window].
-fn visible_rows(
-    len: usize,
-    element_height: u16,
-    widget_size: Size,
+/// Returns the changes of visibility from the `previous` tiles to the
+/// `current` ones.
+fn visibility_changes(previous: &Ranges, current: &Ranges) -> Vec<VisibilityChange> {
+    let mut changes = Vec::new();
+
+    if current.visible != previous.visible {
+        changes.push(VisibilityChange::VisibleRangeChanged {
+            start: current.visible.start,
+            end: current.visible.end,
+        });
+    }
+
+    let entered: Vec<_> = current
+        .built
+        .indices()
+        .filter(|&index| !previous.built.contains(index))
+        .collect();
+    if !entered.is_empty() {
+        changes.push(VisibilityChange::ItemsEntered(entered));
+    }
+
+    let left: Vec<_> = previous
+        .built
+        .indices()
+        .filter(|&index| !current.built.contains(index))
+        .collect();
+    if !left.is_empty() {
+        changes.push(VisibilityChange::ItemsLeft(left));
+    }
+
+    changes
+}
+
+/// A scroll of a [`VirtualGrid`](VirtualGrid) its scrollable does not make
+/// by itself.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Snap {
+    /// The offset to scroll to.
     offset: scrollable::RelativeOffset,
-) -> BoundedRange {
-    let offset = offset.y;
-    let widget_height = widget_height_in_rows(element_height, widget_size);
-    let len = len as f32;
-    let scroll_len = len - widget_height;
-    let first_row = scroll_len * offset;
-    // -0.5 effectively rounds down to bring the number of rows back in range.
-    let last_row = (first_row + widget_height).min(len - 1.0);
-    assert!(last_row < len);
+    /// The tile scrolled to and its alignment, if the scroll is a jump.
+    jumped: Option<(usize, Align)>,
+}
+
+/// The first tile in view of a [`VirtualGrid`](VirtualGrid), and how far
+/// the view is scrolled past its top.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Anchor {
+    key: u64,
+    index: usize,
+    offset: Vector,
+}
+
+/// The local state of a [`VirtualGrid`](VirtualGrid).
+#[derive(Debug, Clone)]
+pub struct State {
+    /// The scrollable of the grid.
+    id: scrollable::Id,
+    /// How far the grid is scrolled.
+    offset: scrollable::RelativeOffset,
+    /// The tiles last reported to be in view.
+    reported: Ranges,
+    /// The tile last scrolled to, until the grid is scrolled by other means.
+    jumped: Option<(usize, Align)>,
+    /// The first tile last reported to be in view.
+    anchor: Option<Anchor>,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            id: scrollable::Id::unique(),
+            offset: scrollable::RelativeOffset::START,
+            reported: Ranges::default(),
+            jumped: None,
+            anchor: None,
+        }
+    }
+}
+
+/// The interactions with a [`VirtualGrid`](VirtualGrid).
+#[derive(Debug, Clone)]
+pub enum Event<Message> {
+    Scrolled(scrollable::RelativeOffset),
+    Visibility(VisibilityChange),
+    /// Every change up to the given tiles has been reported.
+    Reported(Ranges),
+    /// The grid has been scrolled to the tile.
+    Jumped((usize, Align)),
+    /// The tile is the first one in view.
+    Anchored(Anchor),
+    Tile(Message),
+}
+
+impl<'a, Message> Component<Message, Renderer> for VirtualGrid<'a, Message>
+where
+    Message: 'static,
+{
+    type State = State;
+    type Event = Event<Message>;
+
+    fn update(&mut self, state: &mut Self::State, event: Self::Event) -> Option<Message> {
+        match event {
+            Event::Scrolled(offset) => {
+                state.offset = offset;
+                if self.scroll_to.is_none() {
+                    state.jumped = None;
+                }
+                state.anchor = None;
+                self.on_scroll.as_ref().map(|on_scroll| on_scroll(offset))
+            }
+            Event::Visibility(change) => self
+                .on_visibility_change
+                .as_ref()
+                .map(|on_visibility_change| on_visibility_change(change)),
+            Event::Reported(ranges) => {
+                state.reported = ranges;
+                None
+            }
+            Event::Jumped(target) => {
+                state.jumped = Some(target);
+                self.on_jump.as_ref().map(|on_jump| on_jump(target.0))
+            }
+            Event::Anchored(anchor) => {
+                state.anchor = Some(anchor);
+                None
+            }
+            Event::Tile(message) => Some(message),
+        }
+    }
+
+    fn view(&self, state: &Self::State) -> Element<'_, Self::Event> {
+        let state = state.clone();
+
+        responsive(move |size| self.view_tiles(&state, size)).into()
+    }
+}
+
+impl<'a, Message> From<VirtualGrid<'a, Message>> for Element<'a, Message>
+where
+    Message: 'static,
+{
+    fn from(virtual_grid: VirtualGrid<'a, Message>) -> Self {
+        iced_lazy::component(virtual_grid)
+    }
+}
+
+/// Reports the [`VisibilityChange`](VisibilityChange)s of a
+/// [`VirtualGrid`](VirtualGrid) with the first event after they happened,
+/// one message per change, and makes the scrolls the grid asks for.
+struct Report<'a, Message> {
+    /// The content of the grid.
+    content: Element<'a, Event<Message>>,
+    /// The changes to report.
+    changes: Vec<VisibilityChange>,
+    /// The tiles after the changes.
+    ranges: Ranges,
+    /// The scrollable of the grid.
+    id: scrollable::Id,
+    /// The scroll to make.
+    snap: Option<Snap>,
+    /// The first tile in view, if it changed.
+    anchor: Option<Anchor>,
+}
+
+impl<'a, Message> Widget<Event<Message>, Renderer> for Report<'a, Message> {
+    fn children(&self) -> Vec<Tree> {
+        vec![Tree::new(&self.content)]
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        tree.diff_children(std::slice::from_ref(&self.content));
+    }
+
+    fn width(&self) -> Length {
+        self.content.as_widget().width()
+    }
+
+    fn height(&self) -> Length {
+        self.content.as_widget().height()
+    }
+
+    fn layout(&self, renderer: &Renderer, limits: &Limits) -> Node {
+        self.content.as_widget().layout(renderer, limits)
+    }
+
+    fn operate(
+        &self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        operation: &mut dyn Operation<Event<Message>>,
+    ) {
+        self.content
+            .as_widget()
+            .operate(&mut tree.children[0], layout, renderer, operation);
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: NativeEvent,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Event<Message>>,
+    ) -> event::Status {
+        if !self.changes.is_empty() {
+            for change in std::mem::take(&mut self.changes) {
+                shell.publish(Event::Visibility(change));
+            }
+            shell.publish(Event::Reported(self.ranges.clone()));
+        }
+
+        if let Some(Snap { offset, jumped }) = self.snap.take() {
+            let mut snap = operation::scrollable::snap_to(self.id.clone().into(), offset);
+            self.content
+                .as_widget()
+                .operate(&mut tree.children[0], layout, renderer, &mut snap);
+
+            // the scrollable does not report offsets it is snapped to
+            shell.publish(Event::Scrolled(offset));
+            if let Some(target) = jumped {
+                shell.publish(Event::Jumped(target));
+            }
+        }
+
+        // after the scroll, which forgets the previous anchor
+        if let Some(anchor) = self.anchor.take() {
+            shell.publish(Event::Anchored(anchor));
+        }
+
+        self.content.as_widget_mut().on_event(
+            &mut tree.children[0],
+            event,
+            layout,
+            cursor_position,
+            renderer,
+            clipboard,
+            shell,
+        )
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        self.content.as_widget().mouse_interaction(
+            &tree.children[0],
+            layout,
+            cursor_position,
+            viewport,
+            renderer,
+        )
+    }
 
-    BoundedRange {
-        start: first_row as usize,
-        end: last_row as usize,
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &iced::Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+    ) {
+        self.content.as_widget().draw(
+            &tree.children[0],
+            renderer,
+            theme,
+            style,
+            layout,
+            cursor_position,
+            viewport,
+        );
     }
 }
 
-fn visible_tiles(
-    n_columns: usize,
+impl<'a, Message> From<Report<'a, Message>> for Element<'a, Event<Message>>
+where
+    Message: 'static,
+{
+    fn from(report: Report<'a, Message>) -> Self {
+        Element::new(report)
+    }
+}
+
+/// Empty space standing in for the items of a [`VirtualList`](VirtualList)
+/// that are not built.
+///
+/// Unlike [`Space`](iced::widget::Space) its height is not limited to
+/// `u16::MAX` units.
+struct Spacer {
+    height: f32,
+}
+
+impl Spacer {
+    fn new(height: f32) -> Self {
+        Self { height }
+    }
+}
+
+impl<Message, Renderer> Widget<Message, Renderer> for Spacer
+where
+    Renderer: iced_native::Renderer,
+{
+    fn width(&self) -> Length {
+        Length::Shrink
+    }
+
+    fn height(&self) -> Length {
+        Length::Shrink
+    }
+
+    fn layout(&self, _renderer: &Renderer, _limits: &Limits) -> Node {
+        Node::new(Size::new(0.0, self.height))
+    }
+
+    fn draw(
+        &self,
+        _state: &Tree,
+        _renderer: &mut Renderer,
+        _theme: &Renderer::Theme,
+        _style: &renderer::Style,
+        _layout: Layout<'_>,
+        _cursor_position: Point,
+        _viewport: &Rectangle,
+    ) {
+    }
+}
+
+impl<'a, Message, Renderer> From<Spacer> for iced_native::Element<'a, Message, Renderer>
+where
+    Renderer: iced_native::Renderer,
+{
+    fn from(spacer: Spacer) -> Self {
+        iced_native::Element::new(spacer)
+    }
+}
+
+/// A scrollable list of `len` items of different heights, which only builds
+/// the items in view and a few around them.
+///
+/// Items are measured once they are laid out, and the ones that have not been
+/// are assumed to be as tall as the average measured item. The item at the
+/// top of the view stays in place when estimates are replaced by
+/// measurements.
+#[allow(missing_debug_implementations)]
+pub struct VirtualList<'a, Message> {
+    /// The number of items.
     len: usize,
-    element_height: u16,
-    widget_size: Size,
-    offset: scrollable::RelativeOffset,
-) -> BoundedRange {
-    let n_rows = len / n_columns;
-    let visible = visible_rows(n_rows, element_height, widget_size, offset);
+    /// Builds the item at an index.
+    view: Box<dyn Fn(usize) -> Element<'a, Message> + 'a>,
+    /// The height assumed for the items until some are measured.
+    estimated_height: f32,
+    /// The space between two items.
+    spacing: u16,
+    /// How many items are built above and below the ones in view.
+    overscan: usize,
+}
 
-    BoundedRange {
-        start: visible.start * n_columns,
-        end: (visible.end + 1) * n_columns - 1,
+impl<'a, Message> VirtualList<'a, Message> {
+    /// Creates a new [`VirtualList`](VirtualList) of `len` items, building
+    /// the items in view with `view`.
+    pub fn new<F>(len: usize, view: F) -> Self
+    where
+        F: Fn(usize) -> Element<'a, Message> + 'a,
+    {
+        Self {
+            len,
+            view: Box::new(view),
+            estimated_height: DEFAULT_ESTIMATED_HEIGHT,
+            spacing: 0,
+            overscan: DEFAULT_OVERSCAN,
+        }
+    }
+
+    /// Sets the height assumed for the items until some are measured.
+    pub fn estimated_height(self, estimated_height: f32) -> Self {
+        Self {
+            estimated_height,
+            ..self
+        }
+    }
+
+    /// Sets the space between two items.
+    pub fn spacing(self, spacing: u16) -> Self {
+        Self { spacing, ..self }
+    }
+
+    /// Sets how many items are built above and below the ones in view.
+    pub fn overscan(self, overscan: usize) -> Self {
+        Self { overscan, ..self }
+    }
+
+    /// Builds the items in view of a scrollable of `size`.
+    fn view_items(&self, state: &ListState, size: Size) -> Element<'_, ListEvent<Message>>
+    where
+        Message: 'static,
+    {
+        state.heights.borrow_mut().fallback = self.estimated_height;
+
+        let heights = state.heights.borrow();
+        let window = list_window(
+            &heights,
+            self.len,
+            state.anchor,
+            state.scroll_y,
+            size.height,
+            self.overscan,
+        );
+
+        let spacing = f32::from(self.spacing);
+        let items = window.items.clone().map(|index| {
+            Element::from(Measure {
+                index,
+                spacing,
+                content: (self.view)(index).map(ListEvent::Item),
+                heights: Rc::clone(&state.heights),
+            })
+        });
+
+        state.window.set(WindowSnapshot {
+            first: window.items.start,
+            top: window.top,
+            content_height: window.top
+                + window
+                    .items
+                    .clone()
+                    .map(|index| heights.get(index))
+                    .sum::<f32>()
+                + window.bottom,
+            viewport_height: size.height,
+        });
+
+        let content = std::iter::once(Element::from(Spacer::new(window.top)))
+            .chain(items)
+            .chain(std::iter::once(Element::from(Spacer::new(window.bottom))))
+            .collect();
+
+        scrollable(iced::widget::Column::with_children(content).width(Length::Fill))
+            .height(Length::Fill)
+            .vertical_scroll(scrollable::Properties::new())
+            .on_scroll(ListEvent::Scrolled)
+            .into()
     }
 }
 
-mod text_style {
-    use iced::{
-        widget::container::{Appearance, StyleSheet},
-        Color,
-    };
+/// The local state of a [`VirtualList`](VirtualList).
+#[derive(Debug, Clone, Default)]
+pub struct ListState {
+    /// The heights of the items, shared with the items so they are recorded
+    /// when they are laid out.
+    heights: Rc<RefCell<Heights>>,
+    /// How far the list is scrolled, in units.
+    scroll_y: f32,
+    /// The item at the top of the view and how far it is scrolled past.
+    anchor: (usize, f32),
+    /// Where the items were placed by the last view, shared with the views
+    /// built from a copy of the state.
+    window: Rc<Cell<WindowSnapshot>>,
+}
+
+/// Where the items of a [`VirtualList`](VirtualList) were placed by its
+/// last view.
+#[derive(Debug, Clone, Copy, Default)]
+struct WindowSnapshot {
+    /// The index of the first item built.
+    first: usize,
+    /// The height of the space above the first item built.
+    top: f32,
+    /// The height of the whole list.
+    content_height: f32,
+    /// The height of the visible part of the list.
+    viewport_height: f32,
+}
+
+/// The interactions with a [`VirtualList`](VirtualList).
+#[derive(Debug, Clone)]
+pub enum ListEvent<Message> {
+    Scrolled(scrollable::RelativeOffset),
+    /// Items were measured with a height different from the one assumed.
+    Measured,
+    Item(Message),
+}
 
-    #[derive(Debug, Clone, Copy)]
-    pub struct Text;
-
-    impl StyleSheet for Text {
-        type Style = ();
-        fn appearance(&self, _style: &Self::Style) -> Appearance {
-            Appearance {
-                background: Some(iced::Background::Color(Color::from_rgb(0.5, 0.5, 0.5))),
-                border_width: 5.0,
-                border_color: Color::BLACK,
-                ..Default::default()
+impl<'a, Message> Component<Message, Renderer> for VirtualList<'a, Message>
+where
+    Message: 'static,
+{
+    type State = ListState;
+    type Event = ListEvent<Message>;
+
+    fn update(&mut self, state: &mut Self::State, event: Self::Event) -> Option<Message> {
+        match event {
+            ListEvent::Scrolled(offset) => {
+                let window = state.window.get();
+                let scrollable_height = (window.content_height - window.viewport_height).max(0.0);
+
+                state.scroll_y = offset.y * scrollable_height;
+                state.anchor = state.heights.borrow().item_at(
+                    window.first,
+                    window.top,
+                    state.scroll_y,
+                    self.len,
+                );
+                None
             }
+            ListEvent::Measured => None,
+            ListEvent::Item(message) => Some(message),
         }
     }
 
-    impl From<Text> for iced::theme::Container {
-        fn from(_style: Text) -> Self {
-            iced::theme::Container::Box
+    fn view(&self, state: &Self::State) -> Element<'_, Self::Event> {
+        let state = state.clone();
+
+        responsive(move |size| self.view_items(&state, size)).into()
+    }
+}
+
+impl<'a, Message> From<VirtualList<'a, Message>> for Element<'a, Message>
+where
+    Message: 'static,
+{
+    fn from(virtual_list: VirtualList<'a, Message>) -> Self {
+        iced_lazy::component(virtual_list)
+    }
+}
+
+/// The heights of the items of a [`VirtualList`](VirtualList), measured or
+/// estimated.
+#[derive(Debug)]
+struct Heights {
+    /// The height of the items that have been laid out, including the
+    /// spacing below them.
+    measured: BTreeMap<usize, f32>,
+    /// The sum of the `measured` heights.
+    measured_total: f32,
+    /// The height assumed until an item is measured.
+    fallback: f32,
+    /// Whether a measurement changed since the last view.
+    changed: bool,
+}
+
+impl Default for Heights {
+    fn default() -> Self {
+        Self {
+            measured: BTreeMap::new(),
+            measured_total: 0.0,
+            fallback: DEFAULT_ESTIMATED_HEIGHT,
+            changed: false,
         }
     }
 }
 
+impl Heights {
+    /// Returns the height assumed for an item that has not been measured: the
+    /// average of the measured ones, if any.
+    #[allow(clippy::cast_precision_loss)]
+    fn estimate(&self) -> f32 {
+        if self.measured.is_empty() {
+            self.fallback
+        } else {
+            self.measured_total / self.measured.len() as f32
+        }
+    }
+
+    /// Returns the measured or estimated height of the item at `index`.
+    fn get(&self, index: usize) -> f32 {
+        self.measured
+            .get(&index)
+            .copied()
+            .unwrap_or_else(|| self.estimate())
+    }
+
+    /// Records the measured `height` of the item at `index`.
+    fn insert(&mut self, index: usize, height: f32) {
+        let previous = self.measured.insert(index, height);
+        if previous != Some(height) {
+            self.measured_total += height - previous.unwrap_or(0.0);
+            self.changed = true;
+        }
+    }
+
+    /// Returns the total height of the items from `start` to `end`.
+    #[allow(clippy::cast_precision_loss)]
+    fn span(&self, start: usize, end: usize) -> f32 {
+        if end <= start {
+            return 0.0;
+        }
+
+        let (count, sum) = self
+            .measured
+            .range(start..end)
+            .fold((0, 0.0), |(count, sum), (_, height)| {
+                (count + 1, sum + height)
+            });
+
+        sum + (end - start - count) as f32 * self.estimate()
+    }
+
+    /// Finds the item at `y`, given that the item at `start` is at `start_y`.
+    ///
+    /// Returns the index of the item and how far `y` is past its top. Items
+    /// above `start` are assumed to be as tall as estimated.
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss
+    )]
+    fn item_at(&self, start: usize, start_y: f32, y: f32, len: usize) -> (usize, f32) {
+        if len == 0 {
+            return (0, 0.0);
+        }
+
+        let estimate = self.estimate().max(1.0);
+        if y < start_y {
+            let above = ((start_y - y) / estimate).ceil() as usize;
+            let mut index = start.saturating_sub(above);
+            let mut top = start_y - self.span(index, start);
+            while top > y && index > 0 {
+                index -= 1;
+                top -= self.get(index);
+            }
+            return self.item_at(index, top, y.max(top), len);
+        }
+
+        let mut index = start;
+        let mut top = start_y;
+        for (&measured, &height) in self.measured.range(start..len) {
+            let run_end = top + (measured - index) as f32 * estimate;
+            if y < run_end {
+                break;
+            }
+
+            index = measured;
+            top = run_end;
+            if y < top + height {
+                return (index, y - top);
+            }
+
+            index += 1;
+            top += height;
+        }
+
+        let skipped = (((y - top) / estimate).floor() as usize).min(len - 1 - index.min(len - 1));
+        let index = (index + skipped).min(len - 1);
+        top += skipped as f32 * estimate;
+
+        (index, (y - top).max(0.0))
+    }
+}
+
+/// The items of a [`VirtualList`](VirtualList) that are built and the space
+/// around them.
+#[derive(Debug, Clone, PartialEq)]
+struct ListWindow {
+    /// The indices of the items built.
+    items: Range<usize>,
+    /// The height of the space above the items built.
+    top: f32,
+    /// The height of the space below the items built.
+    bottom: f32,
+}
+
+/// Finds the items in a view of `viewport_height` scrolled to `scroll_y`,
+/// with the `anchor` item at the top, and `overscan` items around them.
+///
+/// The space above is sized so the anchor stays at `scroll_y` even if the
+/// items above it were measured since it was found, except at the very top of
+/// the list where there is nothing to make up for the difference.
+fn list_window(
+    heights: &Heights,
+    len: usize,
+    anchor: (usize, f32),
+    scroll_y: f32,
+    viewport_height: f32,
+    overscan: usize,
+) -> ListWindow {
+    let (anchor, past_anchor) = (anchor.0.min(len.saturating_sub(1)), anchor.1);
+
+    let mut end = anchor;
+    let mut bottom_of_view = -past_anchor;
+    while end < len && bottom_of_view < viewport_height {
+        bottom_of_view += heights.get(end);
+        end += 1;
+    }
+
+    let items = anchor.saturating_sub(overscan)..(end + overscan).min(len);
+    let top = if items.start == 0 {
+        0.0
+    } else {
+        (scroll_y - past_anchor - heights.span(items.start, anchor)).max(0.0)
+    };
+    let bottom = heights.span(items.end, len);
+
+    ListWindow { items, top, bottom }
+}
+
+/// An item of a [`VirtualList`](VirtualList), recording its height whenever
+/// it is laid out.
+struct Measure<'a, Message> {
+    /// The index of the item.
+    index: usize,
+    /// The space below the item.
+    spacing: f32,
+    /// The item.
+    content: Element<'a, ListEvent<Message>>,
+    /// The heights of the items of the list.
+    heights: Rc<RefCell<Heights>>,
+}
+
+impl<'a, Message> Widget<ListEvent<Message>, Renderer> for Measure<'a, Message> {
+    fn children(&self) -> Vec<Tree> {
+        vec![Tree::new(&self.content)]
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        tree.diff_children(std::slice::from_ref(&self.content));
+    }
+
+    fn width(&self) -> Length {
+        self.content.as_widget().width()
+    }
+
+    fn height(&self) -> Length {
+        Length::Shrink
+    }
+
+    fn layout(&self, renderer: &Renderer, limits: &Limits) -> Node {
+        let content = self.content.as_widget().layout(renderer, limits);
+        let size = Size::new(content.size().width, content.size().height + self.spacing);
+        self.heights.borrow_mut().insert(self.index, size.height);
+
+        Node::with_children(size, vec![content])
+    }
+
+    fn operate(
+        &self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        operation: &mut dyn Operation<ListEvent<Message>>,
+    ) {
+        if let Some(content) = layout.children().next() {
+            self.content
+                .as_widget()
+                .operate(&mut tree.children[0], content, renderer, operation);
+        }
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: NativeEvent,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, ListEvent<Message>>,
+    ) -> event::Status {
+        // the list is built again with the new heights on the next event
+        if std::mem::take(&mut self.heights.borrow_mut().changed) {
+            shell.publish(ListEvent::Measured);
+        }
+
+        let Some(content) = layout.children().next() else {
+            return event::Status::Ignored;
+        };
+
+        self.content.as_widget_mut().on_event(
+            &mut tree.children[0],
+            event,
+            content,
+            cursor_position,
+            renderer,
+            clipboard,
+            shell,
+        )
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        layout
+            .children()
+            .next()
+            .map_or_else(mouse::Interaction::default, |content| {
+                self.content.as_widget().mouse_interaction(
+                    &tree.children[0],
+                    content,
+                    cursor_position,
+                    viewport,
+                    renderer,
+                )
+            })
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &iced::Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+    ) {
+        if let Some(content) = layout.children().next() {
+            self.content.as_widget().draw(
+                &tree.children[0],
+                renderer,
+                theme,
+                style,
+                content,
+                cursor_position,
+                viewport,
+            );
+        }
+    }
+}
+
+impl<'a, Message> From<Measure<'a, Message>> for Element<'a, ListEvent<Message>>
+where
+    Message: 'static,
+{
+    fn from(measure: Measure<'a, Message>) -> Self {
+        Element::new(measure)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn heights(estimate: f32, measured: &[(usize, f32)]) -> Heights {
+        let mut heights = Heights {
+            fallback: estimate,
+            ..Heights::default()
+        };
+        for &(index, height) in measured {
+            heights.insert(index, height);
+        }
+        heights
+    }
+
+    #[test]
+    fn heights_estimate_from_measured_items() {
+        let heights = heights(40.0, &[(1, 10.0), (2, 30.0)]);
+
+        assert_eq!(heights.get(1), 10.0);
+        assert_eq!(heights.get(5), 20.0);
+        assert_eq!(heights.span(0, 4), 80.0);
+        assert_eq!(Heights::default().get(0), DEFAULT_ESTIMATED_HEIGHT);
+    }
+
+    #[test]
+    fn item_at_walks_measured_and_estimated_items() {
+        let heights = heights(20.0, &[(2, 50.0), (3, 10.0)]);
+        // estimate is 30: items 0 and 1 span 0..60, item 2 60..110, item 3 110..120
+
+        assert_eq!(heights.item_at(0, 0.0, 45.0, 10), (1, 15.0));
+        assert_eq!(heights.item_at(0, 0.0, 70.0, 10), (2, 10.0));
+        assert_eq!(heights.item_at(0, 0.0, 115.0, 10), (3, 5.0));
+        assert_eq!(heights.item_at(0, 0.0, 125.0, 10), (4, 5.0));
+        assert_eq!(heights.item_at(0, 0.0, 10_000.0, 10).0, 9);
+        assert_eq!(heights.item_at(4, 120.0, 90.0, 10), (2, 30.0));
+    }
+
+    #[test]
+    fn list_window_builds_items_in_view_and_overscan() {
+        let heights = heights(10.0, &[]);
+
+        let window = list_window(&heights, 100, (20, 5.0), 205.0, 50.0, 2);
+
+        assert_eq!(window.items, 18..28);
+        assert_eq!(window.top, 180.0);
+        assert_eq!(window.bottom, 720.0);
+    }
+
+    #[test]
+    fn list_window_keeps_anchor_in_place_after_measuring() {
+        let mut heights = heights(10.0, &[(0, 10.0)]);
+        let before = list_window(&heights, 100, (20, 5.0), 205.0, 50.0, 2);
+
+        heights.insert(18, 40.0);
+        heights.insert(19, 40.0);
+        let after = list_window(&heights, 100, (20, 5.0), 205.0, 50.0, 2);
+
+        let anchor_top = |window: &ListWindow| window.top + heights.span(window.items.start, 20);
+        assert_eq!(anchor_top(&after), 200.0);
+        assert_eq!(before.items.start, after.items.start);
+    }
+
     #[test]
-    fn get_visible_rows_in_widget() {
-        let row_height = 128;
-        let n_elements = 100;
-        let widget_size = Size {
-            height: 598.0,
-            width: 500.0,
+    fn list_window_without_items() {
+        let window = list_window(&Heights::default(), 0, (0, 0.0), 0.0, 100.0, 2);
+
+        assert_eq!(window.items, 0..0);
+        assert_eq!(window.top, 0.0);
+    }
+
+    /// Returns the tiles of 100 by 100 units in the `x`, `y`, `width` by
+    /// `height` part of a grid of `columns` columns.
+    fn tiles(columns: usize, x: f32, y: f32, width: f32, height: f32) -> VisibleTiles {
+        TileLayout::new(100, Size::new(100.0, 100.0)).visible(
+            columns,
+            Viewport::new(Vector::new(x, y), Size::new(width, height)),
+        )
+    }
+
+    #[test]
+    fn visibility_changes_report_entered_and_left_tiles() {
+        let previous = Ranges {
+            visible: 4..8,
+            built: tiles(1, 0.0, 200.0, 100.0, 800.0),
+        };
+        let current = Ranges {
+            visible: 6..10,
+            built: tiles(1, 0.0, 400.0, 100.0, 800.0),
         };
 
-        let offset = |y| scrollable::RelativeOffset { x: 0.0, y };
+        assert_eq!(
+            visibility_changes(&previous, &current),
+            vec![
+                VisibilityChange::VisibleRangeChanged { start: 6, end: 10 },
+                VisibilityChange::ItemsEntered(vec![10, 11]),
+                VisibilityChange::ItemsLeft(vec![2, 3]),
+            ]
+        );
+        assert!(visibility_changes(&current, &current).is_empty());
+    }
 
-        let tests = vec![
-            (offset(0.0), BoundedRange { start: 0, end: 4 }),
-            (offset(0.5), BoundedRange { start: 47, end: 52 }),
-            (offset(1.0), BoundedRange { start: 95, end: 99 }),
-        ];
+    #[test]
+    fn visibility_changes_from_nothing() {
+        let current = Ranges {
+            visible: 0..2,
+            built: tiles(1, 0.0, 0.0, 100.0, 400.0),
+        };
 
-        for (offset, expected) in tests {
-            let result = visible_rows(n_elements, row_height, widget_size, offset);
-            assert_eq!(expected, result);
-        }
+        assert_eq!(
+            visibility_changes(&Ranges::default(), &current),
+            vec![
+                VisibilityChange::VisibleRangeChanged { start: 0, end: 2 },
+                VisibilityChange::ItemsEntered(vec![0, 1, 2, 3]),
+            ]
+        );
+    }
+
+    #[test]
+    fn visibility_changes_of_columns() {
+        let previous = Ranges {
+            visible: 0..20,
+            built: tiles(10, 0.0, 0.0, 300.0, 200.0),
+        };
+        let current = Ranges {
+            visible: 0..20,
+            built: tiles(10, 100.0, 0.0, 300.0, 200.0),
+        };
+
+        assert_eq!(
+            visibility_changes(&previous, &current),
+            vec![
+                VisibilityChange::ItemsEntered(vec![3, 13]),
+                VisibilityChange::ItemsLeft(vec![0, 10]),
+            ]
+        );
     }
 }
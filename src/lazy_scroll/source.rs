@@ -0,0 +1,433 @@
+//! Where the items of a lazily scrolled view come from, a page at a time.
+use std::{
+    collections::{HashMap, HashSet},
+    marker::PhantomData,
+    path::PathBuf,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use async_std::io::{prelude::*, BufReader, SeekFrom};
+use iced::futures::future::BoxFuture;
+use serde::de::DeserializeOwned;
+
+/// A source of items fetched a page at a time.
+pub trait DataSource {
+    /// The type of the items.
+    type Item;
+
+    /// Returns the number of items, if it is known before the last page is
+    /// fetched.
+    fn total(&self) -> Option<usize>;
+
+    /// Returns the number of items in a page, the last one excepted.
+    fn page_size(&self) -> usize;
+
+    /// Fetches the page at `index`, starting at item `index * page_size`.
+    fn fetch(&self, index: usize) -> BoxFuture<'static, Result<Page<Self::Item>, FetchError>>;
+}
+
+/// The items of a page fetched from a [`DataSource`](DataSource).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page<T> {
+    /// The items, as many as the page size unless the page is the last one.
+    pub items: Vec<T>,
+    /// Whether there are no items after this page.
+    pub is_last: bool,
+}
+
+/// Why a page could not be fetched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FetchError {
+    File,
+    Format,
+}
+
+/// A [`DataSource`](DataSource) of items kept in memory.
+#[derive(Debug, Clone)]
+pub struct MemorySource<T> {
+    items: Arc<Vec<T>>,
+    page_size: usize,
+    is_length_known: bool,
+}
+
+impl<T> MemorySource<T> {
+    /// Creates a new [`MemorySource`](MemorySource) serving `items` in pages
+    /// of `page_size`.
+    pub fn new(items: Vec<T>, page_size: usize) -> Self {
+        Self {
+            items: Arc::new(items),
+            page_size: page_size.max(1),
+            is_length_known: true,
+        }
+    }
+
+    /// Hides the number of items until the last page is fetched, like a
+    /// source that streams its items.
+    pub fn unknown_length(self) -> Self {
+        Self {
+            is_length_known: false,
+            ..self
+        }
+    }
+}
+
+impl<T> DataSource for MemorySource<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    type Item = T;
+
+    fn total(&self) -> Option<usize> {
+        self.is_length_known.then_some(self.items.len())
+    }
+
+    fn page_size(&self) -> usize {
+        self.page_size
+    }
+
+    fn fetch(&self, index: usize) -> BoxFuture<'static, Result<Page<T>, FetchError>> {
+        let items = Arc::clone(&self.items);
+        let page_size = self.page_size;
+
+        Box::pin(async move {
+            let start = index.saturating_mul(page_size).min(items.len());
+            let end = start.saturating_add(page_size).min(items.len());
+
+            Ok(Page {
+                items: items[start..end].to_vec(),
+                is_last: end == items.len(),
+            })
+        })
+    }
+}
+
+/// A [`DataSource`](DataSource) reading a file with an item in JSON on
+/// every line.
+///
+/// Blank lines are skipped. The number of items is only known once the last
+/// page is fetched.
+///
+/// Where the pages start in the file is remembered as they are found, so
+/// that a page is read without reading the pages before it again. The file
+/// is not expected to change.
+#[derive(Debug, Clone)]
+pub struct JsonLinesSource<T> {
+    path: PathBuf,
+    page_size: usize,
+    /// Where the pages found so far start in the file, by page index.
+    page_starts: Arc<Mutex<Vec<u64>>>,
+    item: PhantomData<fn() -> T>,
+}
+
+impl<T> JsonLinesSource<T> {
+    /// Creates a new [`JsonLinesSource`](JsonLinesSource) reading the file at
+    /// `path` in pages of `page_size` lines.
+    pub fn new(path: impl Into<PathBuf>, page_size: usize) -> Self {
+        Self {
+            path: path.into(),
+            page_size: page_size.max(1),
+            page_starts: Arc::new(Mutex::new(vec![0])),
+            item: PhantomData,
+        }
+    }
+}
+
+impl<T> DataSource for JsonLinesSource<T>
+where
+    T: DeserializeOwned + Send + 'static,
+{
+    type Item = T;
+
+    fn total(&self) -> Option<usize> {
+        None
+    }
+
+    fn page_size(&self) -> usize {
+        self.page_size
+    }
+
+    fn fetch(&self, index: usize) -> BoxFuture<'static, Result<Page<T>, FetchError>> {
+        let path = self.path.clone();
+        let page_size = self.page_size;
+        let page_starts = Arc::clone(&self.page_starts);
+
+        Box::pin(async move {
+            // start from the closest page known to start before the one fetched
+            let (mut page, mut position) = {
+                let starts = page_starts.lock().unwrap_or_else(PoisonError::into_inner);
+                let page = index.min(starts.len() - 1);
+                (page, starts[page])
+            };
+
+            let mut file = async_std::fs::File::open(path)
+                .await
+                .map_err(|_| FetchError::File)?;
+            file.seek(SeekFrom::Start(position))
+                .await
+                .map_err(|_| FetchError::File)?;
+            let mut reader = BufReader::new(file);
+
+            let mut line = String::new();
+            let mut lines_in_page = 0;
+            let mut items = Vec::new();
+
+            loop {
+                let line_start = position;
+                line.clear();
+                let read = reader
+                    .read_line(&mut line)
+                    .await
+                    .map_err(|_| FetchError::File)?;
+                if read == 0 {
+                    return Ok(Page {
+                        items,
+                        is_last: true,
+                    });
+                }
+                position += u64::try_from(read).map_err(|_| FetchError::File)?;
+
+                if line.trim().is_empty() {
+                    continue;
+                }
+
+                if lines_in_page == page_size {
+                    page += 1;
+                    lines_in_page = 0;
+
+                    let mut starts = page_starts.lock().unwrap_or_else(PoisonError::into_inner);
+                    if starts.len() == page {
+                        starts.push(line_start);
+                    }
+                }
+                if page > index {
+                    return Ok(Page {
+                        items,
+                        is_last: false,
+                    });
+                }
+
+                lines_in_page += 1;
+                if page == index {
+                    items.push(serde_json::from_str(&line).map_err(|_| FetchError::Format)?);
+                }
+            }
+        })
+    }
+}
+
+/// The items of a [`DataSource`](DataSource) fetched so far.
+#[allow(missing_debug_implementations)]
+pub struct Paged<T> {
+    source: Box<dyn DataSource<Item = T>>,
+    /// The items of the pages fetched, by page index.
+    pages: HashMap<usize, Vec<T>>,
+    /// The pages being fetched.
+    pending: HashSet<usize>,
+    /// Why the pages that could not be fetched failed, until they are
+    /// fetched again.
+    failed: HashMap<usize, FetchError>,
+    /// The number of items, once it is known.
+    total: Option<usize>,
+}
+
+impl<T> Paged<T> {
+    /// Creates a new [`Paged`](Paged) fetching items from `source`.
+    pub fn new(source: impl DataSource<Item = T> + 'static) -> Self {
+        Self {
+            total: source.total(),
+            source: Box::new(source),
+            pages: HashMap::new(),
+            pending: HashSet::new(),
+            failed: HashMap::new(),
+        }
+    }
+
+    /// Returns the number of items to show.
+    ///
+    /// Until the number of items is known, that is the items fetched and a
+    /// page of placeholders after them, which fetches the next page once it
+    /// is reached.
+    pub fn len(&self) -> usize {
+        self.total.unwrap_or_else(|| {
+            let fetched = self.pages.keys().max().map_or(0, |last| last + 1);
+            (fetched + 1) * self.page_size()
+        })
+    }
+
+    /// Returns whether the number of items is still unknown.
+    pub fn is_infinite(&self) -> bool {
+        self.total.is_none()
+    }
+
+    /// Returns the number of pages fetched.
+    pub fn fetched_pages(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// Returns the item at `index`, if its page has been fetched.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        let page_size = self.page_size();
+        self.pages.get(&(index / page_size))?.get(index % page_size)
+    }
+
+    /// Returns why the page of the item at `index` could not be fetched, if
+    /// it failed the last time it was.
+    pub fn error(&self, index: usize) -> Option<FetchError> {
+        self.failed.get(&(index / self.page_size())).copied()
+    }
+
+    /// Returns the pages of the `items` that are neither fetched nor being
+    /// fetched, which are then considered to be.
+    ///
+    /// Pages that could not be fetched are fetched again.
+    pub fn missing_pages(&mut self, items: impl IntoIterator<Item = usize>) -> Vec<usize> {
+        let page_size = self.page_size();
+        let len = self.len();
+        let mut missing: Vec<_> = items
+            .into_iter()
+            .filter(|&index| index < len)
+            .map(|index| index / page_size)
+            .filter(|page| !self.pages.contains_key(page))
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .filter(|&page| self.pending.insert(page))
+            .collect();
+        missing.sort_unstable();
+
+        for page in &missing {
+            self.failed.remove(page);
+        }
+
+        missing
+    }
+
+    /// Fetches the page at `index` from the source.
+    pub fn fetch(&self, index: usize) -> BoxFuture<'static, Result<Page<T>, FetchError>> {
+        self.source.fetch(index)
+    }
+
+    /// Stores the page at `index` once it has been fetched.
+    ///
+    /// A page that could not be fetched keeps its error until it is fetched
+    /// again, the next time its items are needed.
+    pub fn insert(&mut self, index: usize, page: Result<Page<T>, FetchError>) {
+        self.pending.remove(&index);
+
+        match page {
+            Ok(page) => {
+                if page.is_last {
+                    self.total = Some(index * self.page_size() + page.items.len());
+                }
+                self.pages.insert(index, page.items);
+            }
+            Err(error) => {
+                self.failed.insert(index, error);
+            }
+        }
+    }
+
+    fn page_size(&self) -> usize {
+        self.source.page_size().max(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use async_std::task::block_on;
+
+    #[test]
+    fn memory_source_serves_pages() {
+        let source = MemorySource::new((0..5).collect(), 2);
+
+        assert_eq!(source.total(), Some(5));
+        assert_eq!(
+            block_on(source.fetch(1)),
+            Ok(Page {
+                items: vec![2, 3],
+                is_last: false
+            })
+        );
+        assert_eq!(
+            block_on(source.fetch(2)),
+            Ok(Page {
+                items: vec![4],
+                is_last: true
+            })
+        );
+        assert_eq!(block_on(source.fetch(7)).map(|page| page.items), Ok(vec![]));
+    }
+
+    #[test]
+    fn json_lines_source_reads_pages() {
+        let path = std::env::temp_dir().join(format!("json-lines-{}.jsonl", std::process::id()));
+        std::fs::write(&path, "\"a\"\n\"b\"\n\n\"c\"\n").unwrap();
+        let source = JsonLinesSource::<String>::new(&path, 2);
+
+        let second = block_on(source.fetch(1));
+        let first = block_on(source.fetch(0));
+        let past_end = block_on(source.fetch(3));
+
+        std::fs::write(&path, "\"a\"\nnot json\n").unwrap();
+        let invalid = block_on(source.fetch(0));
+        std::fs::remove_file(&path).unwrap();
+        let missing = block_on(source.fetch(0));
+
+        assert_eq!(
+            first,
+            Ok(Page {
+                items: vec!["a".to_owned(), "b".to_owned()],
+                is_last: false
+            })
+        );
+        assert_eq!(
+            second,
+            Ok(Page {
+                items: vec!["c".to_owned()],
+                is_last: true
+            })
+        );
+        assert_eq!(
+            past_end,
+            Ok(Page {
+                items: vec![],
+                is_last: true
+            })
+        );
+        assert_eq!(invalid, Err(FetchError::Format));
+        assert_eq!(missing, Err(FetchError::File));
+    }
+
+    #[test]
+    fn paged_fetches_missing_pages_once() {
+        let mut paged = Paged::new(MemorySource::new((0..10).collect(), 4));
+
+        assert_eq!(paged.missing_pages(2..6), vec![0, 1]);
+        assert_eq!(paged.missing_pages(0..10), vec![2]);
+        assert_eq!(paged.get(5), None);
+
+        paged.insert(1, block_on(paged.fetch(1)));
+        assert_eq!(paged.get(5), Some(&5));
+
+        paged.insert(0, Err(FetchError::File));
+        assert_eq!(paged.error(3), Some(FetchError::File));
+        assert_eq!(paged.missing_pages(0..1), vec![0]);
+        assert_eq!(paged.error(3), None);
+    }
+
+    #[test]
+    fn paged_grows_until_last_page() {
+        let mut paged = Paged::new(MemorySource::new((0..5).collect(), 2).unknown_length());
+        assert!(paged.is_infinite());
+        assert_eq!(paged.len(), 2);
+
+        for page in 0..3 {
+            paged.insert(page, block_on(paged.fetch(page)));
+        }
+
+        assert!(!paged.is_infinite());
+        assert_eq!(paged.len(), 5);
+        assert_eq!(paged.get(4), Some(&4));
+    }
+}
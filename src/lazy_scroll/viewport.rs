@@ -0,0 +1,769 @@
+//! Find the tiles of a grid of same-sized tiles that are in view of a
+//! scrollable.
+//!
+//! Every function returns an empty range rather than panicking, whether the
+//! grid has no tiles, no room for a single column or a window of zero size.
+use std::ops::Range;
+
+use iced::{widget::scrollable::RelativeOffset, Padding, Point, Rectangle, Size, Vector};
+
+/// How tiles of the same size are laid out in rows.
+#[derive(Debug, Clone, Copy)]
+pub struct TileLayout {
+    /// The number of tiles.
+    pub len: usize,
+    /// The size of a tile.
+    pub tile: Size,
+    /// The space between two columns.
+    pub column_spacing: f32,
+    /// The space between two rows.
+    pub row_spacing: f32,
+    /// The space around the tiles.
+    pub padding: Padding,
+}
+
+impl PartialEq for TileLayout {
+    fn eq(&self, other: &Self) -> bool {
+        // `Padding` cannot be compared
+        let sides = |padding: Padding| (padding.top, padding.right, padding.bottom, padding.left);
+
+        self.len == other.len
+            && self.tile == other.tile
+            && self.column_spacing == other.column_spacing
+            && self.row_spacing == other.row_spacing
+            && sides(self.padding) == sides(other.padding)
+    }
+}
+
+impl TileLayout {
+    /// Creates a new [`TileLayout`](TileLayout) of `len` tiles of the given
+    /// size, without spacing nor padding.
+    pub fn new(len: usize, tile: Size) -> Self {
+        Self {
+            len,
+            tile,
+            column_spacing: 0.0,
+            row_spacing: 0.0,
+            padding: Padding::ZERO,
+        }
+    }
+
+    /// Sets the space between two rows and between two columns.
+    pub fn spacing(self, spacing: f32) -> Self {
+        Self {
+            column_spacing: spacing,
+            row_spacing: spacing,
+            ..self
+        }
+    }
+
+    /// Returns how many columns fit in `width`, which may be none.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn columns(&self, width: f32) -> usize {
+        let available = width - f32::from(self.padding.horizontal());
+        let pitch = self.tile.width + self.column_spacing;
+        if available < self.tile.width || pitch <= 0.0 {
+            return 0;
+        }
+        if available.is_infinite() {
+            return self.len;
+        }
+
+        ((available + self.column_spacing) / pitch).floor() as usize
+    }
+
+    /// Returns how many rows `columns` columns of tiles take, counting a last
+    /// row that is only partly filled.
+    pub fn rows(&self, columns: usize) -> usize {
+        if columns == 0 {
+            0
+        } else {
+            self.len.div_ceil(columns)
+        }
+    }
+
+    /// Returns the size of the tiles laid out in `columns` columns, padding
+    /// included.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn content_size(&self, columns: usize) -> Size {
+        let extent = |count: usize, size: f32, spacing: f32| {
+            if count == 0 {
+                0.0
+            } else {
+                count as f32 * size + (count - 1) as f32 * spacing
+            }
+        };
+
+        Size::new(
+            extent(columns, self.tile.width, self.column_spacing)
+                + f32::from(self.padding.horizontal()),
+            extent(self.rows(columns), self.tile.height, self.row_spacing)
+                + f32::from(self.padding.vertical()),
+        )
+    }
+
+    /// Returns the tiles in the `viewport` when they are laid out in
+    /// `columns` columns.
+    pub fn visible(&self, columns: usize, viewport: Viewport) -> VisibleTiles {
+        let rows = visible_tracks(
+            self.rows(columns),
+            self.tile.height,
+            self.row_spacing,
+            viewport.offset.y - f32::from(self.padding.top),
+            viewport.size.height,
+        );
+        let columns_in_view = visible_tracks(
+            columns.min(self.len),
+            self.tile.width,
+            self.column_spacing,
+            viewport.offset.x - f32::from(self.padding.left),
+            viewport.size.width,
+        );
+
+        VisibleTiles {
+            rows,
+            columns: columns_in_view,
+            n_columns: columns,
+            len: self.len,
+        }
+    }
+
+    /// Returns the rectangle covering the `tiles`, without the spacing
+    /// around them, relative to the top left corner of the content.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn bounds(&self, tiles: &VisibleTiles) -> Rectangle {
+        if tiles.is_empty() {
+            return Rectangle::new(Point::ORIGIN, Size::ZERO);
+        }
+
+        let extent = |tracks: &Range<usize>, size: f32, spacing: f32| {
+            (
+                tracks.start as f32 * (size + spacing),
+                tracks.len() as f32 * (size + spacing) - spacing,
+            )
+        };
+        let (x, width) = extent(&tiles.columns, self.tile.width, self.column_spacing);
+        let (y, height) = extent(&tiles.rows, self.tile.height, self.row_spacing);
+
+        Rectangle {
+            x: f32::from(self.padding.left) + x,
+            y: f32::from(self.padding.top) + y,
+            width,
+            height,
+        }
+    }
+
+    /// Returns the position of the tile at `index` in the content, when
+    /// tiles are laid out in `columns` columns.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn position(&self, index: usize, columns: usize) -> Vector {
+        let (row, column) = index
+            .checked_div(columns)
+            .zip(index.checked_rem(columns))
+            .unwrap_or((0, 0));
+
+        Vector::new(
+            f32::from(self.padding.left) + column as f32 * (self.tile.width + self.column_spacing),
+            f32::from(self.padding.top) + row as f32 * (self.tile.height + self.row_spacing),
+        )
+    }
+
+    /// Returns the offset that scrolls the `viewport` to the tile at `index`
+    /// when tiles are laid out in `columns` columns, the inverse of
+    /// [`visible`](Self::visible).
+    ///
+    /// The offset of the `viewport` is kept if there is no such tile.
+    pub fn offset_of(
+        &self,
+        index: usize,
+        columns: usize,
+        viewport: Viewport,
+        align: Align,
+    ) -> RelativeOffset {
+        let content = self.content_size(columns);
+        if columns == 0 || index >= self.len {
+            return viewport.relative(content);
+        }
+
+        let tile = Rectangle::new(Point::ORIGIN + self.position(index, columns), self.tile);
+
+        viewport.offset_to(tile, content, align)
+    }
+
+    /// Returns the first tile in the `viewport` when tiles are laid out in
+    /// `columns` columns, and how far the `viewport` is scrolled past its
+    /// top left corner.
+    pub fn anchor(&self, columns: usize, viewport: Viewport) -> Option<(usize, Vector)> {
+        let visible = self.visible(columns, viewport);
+        let first = visible.rows.start * columns + visible.columns.start;
+        if visible.is_empty() || first >= self.len {
+            return None;
+        }
+
+        Some((first, viewport.offset - self.position(first, columns)))
+    }
+
+    /// Returns the offset that brings the tile at `index` back to `offset`
+    /// units above and to the left of the `viewport`, the inverse of
+    /// [`anchor`](Self::anchor), unless it is there already.
+    pub fn reanchor(
+        &self,
+        columns: usize,
+        viewport: Viewport,
+        index: usize,
+        offset: Vector,
+    ) -> Option<RelativeOffset> {
+        viewport.reanchor(
+            self.content_size(columns),
+            self.position(index, columns),
+            offset,
+        )
+    }
+}
+
+/// Where a tile ends up in the viewport once it is scrolled to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Align {
+    /// At the top, or the left.
+    Start,
+    /// In the middle.
+    Center,
+    /// At the bottom, or the right.
+    End,
+    /// Wherever it takes the least scrolling, which is none if the tile is
+    /// already in view.
+    #[default]
+    Nearest,
+}
+
+/// The part of the content of a scrollable that is in view.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    /// How far the content is scrolled, in units.
+    pub offset: Vector,
+    /// The size of the visible part of the content.
+    pub size: Size,
+}
+
+impl Viewport {
+    /// Creates a new [`Viewport`](Viewport) of the given `size` scrolled by
+    /// `offset` units.
+    pub fn new(offset: Vector, size: Size) -> Self {
+        Self { offset, size }
+    }
+
+    /// Creates the [`Viewport`](Viewport) of a scrollable of `size`, scrolled
+    /// to the `relative` offset of `content`.
+    ///
+    /// Content smaller than the scrollable cannot be scrolled, so it is not.
+    pub fn from_relative(relative: RelativeOffset, size: Size, content: Size) -> Self {
+        let offset = |relative: f32, size: f32, content: f32| {
+            let scrollable = (content - size).max(0.0);
+            if relative.is_finite() {
+                relative.clamp(0.0, 1.0) * scrollable
+            } else {
+                0.0
+            }
+        };
+
+        Self {
+            offset: Vector::new(
+                offset(relative.x, size.width, content.width),
+                offset(relative.y, size.height, content.height),
+            ),
+            size,
+        }
+    }
+
+    /// Returns the offset that shows a `tile` of the `content`, where `align`
+    /// says.
+    pub fn offset_to(&self, tile: Rectangle, content: Size, align: Align) -> RelativeOffset {
+        RelativeOffset {
+            x: align_track(
+                tile.x,
+                tile.width,
+                self.offset.x,
+                self.size.width,
+                content.width,
+                align,
+            ),
+            y: align_track(
+                tile.y,
+                tile.height,
+                self.offset.y,
+                self.size.height,
+                content.height,
+                align,
+            ),
+        }
+    }
+
+    /// Returns the first of the `tiles` in view, and how far the viewport is
+    /// scrolled past its top left corner.
+    pub fn anchor(&self, tiles: &[Rectangle]) -> Option<(usize, Vector)> {
+        let view = Rectangle::new(Point::ORIGIN + self.offset, self.size);
+
+        tiles
+            .iter()
+            .position(|tile| tile.intersection(&view).is_some())
+            .map(|index| {
+                (
+                    index,
+                    self.offset - Vector::new(tiles[index].x, tiles[index].y),
+                )
+            })
+    }
+
+    /// Returns the offset scrolling `offset` units past a tile now at
+    /// `position` of the `content`, if the viewport has to move for it.
+    pub fn reanchor(
+        &self,
+        content: Size,
+        position: Vector,
+        offset: Vector,
+    ) -> Option<RelativeOffset> {
+        let anchored = Viewport::new(position + offset, self.size).relative(content);
+
+        // compared once clamped, so a tile that cannot be scrolled back in
+        // place is not snapped to again and again
+        let moved = Viewport::from_relative(anchored, self.size, content).offset - self.offset;
+        (moved.x.abs() >= 0.5 || moved.y.abs() >= 0.5).then_some(anchored)
+    }
+
+    /// Returns how far the viewport is scrolled relative to the `content`,
+    /// the inverse of [`from_relative`](Self::from_relative).
+    pub fn relative(&self, content: Size) -> RelativeOffset {
+        let relative = |offset: f32, size: f32, content: f32| {
+            let scrollable = content - size;
+            if scrollable > 0.0 {
+                (offset / scrollable).clamp(0.0, 1.0)
+            } else {
+                0.0
+            }
+        };
+
+        RelativeOffset {
+            x: relative(self.offset.x, self.size.width, content.width),
+            y: relative(self.offset.y, self.size.height, content.height),
+        }
+    }
+}
+
+/// The tiles of a [`TileLayout`](TileLayout) in a [`Viewport`](Viewport).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VisibleTiles {
+    /// The rows in view.
+    pub rows: Range<usize>,
+    /// The columns in view.
+    pub columns: Range<usize>,
+    /// The number of columns of the layout.
+    n_columns: usize,
+    /// The number of tiles of the layout.
+    len: usize,
+}
+
+impl VisibleTiles {
+    /// Returns whether no tile is in view.
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty() || self.columns.is_empty()
+    }
+
+    /// Returns the indices of the tiles in the visible rows, from the first
+    /// column to the last.
+    pub fn range(&self) -> Range<usize> {
+        if self.is_empty() {
+            return 0..0;
+        }
+
+        let start = (self.rows.start * self.n_columns).min(self.len);
+        let end = (self.rows.end * self.n_columns).min(self.len);
+        start..end
+    }
+
+    /// Returns the indices of the tiles in view, row by row, leaving out the
+    /// columns that are not.
+    pub fn indices(&self) -> impl Iterator<Item = usize> + '_ {
+        self.rows
+            .clone()
+            .flat_map(move |row| {
+                self.columns
+                    .clone()
+                    .map(move |column| row * self.n_columns + column)
+            })
+            .take_while(move |&index| index < self.len)
+    }
+
+    /// Returns whether the tile at `index` is in view.
+    pub fn contains(&self, index: usize) -> bool {
+        index < self.len
+            && self.n_columns > 0
+            && self.rows.contains(&(index / self.n_columns))
+            && self.columns.contains(&(index % self.n_columns))
+    }
+
+    /// Adds `rows` rows and `columns` columns on every side of the visible
+    /// tiles, without going past the tiles of the layout.
+    pub fn with_overscan(self, rows: usize, columns: usize) -> Self {
+        if self.is_empty() {
+            return self;
+        }
+
+        let n_rows = self.len.div_ceil(self.n_columns);
+        let grow = |range: Range<usize>, by: usize, count: usize| {
+            range.start.saturating_sub(by)..range.end.saturating_add(by).min(count)
+        };
+
+        Self {
+            rows: grow(self.rows, rows, n_rows),
+            columns: grow(self.columns, columns, self.n_columns.min(self.len)),
+            ..self
+        }
+    }
+}
+
+/// Returns the tracks of `size` separated by `spacing` that intersect
+/// `extent` units starting at `offset`, out of `count` tracks.
+///
+/// A track only counts if some of it is in view, not only the spacing after
+/// it.
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss
+)]
+fn visible_tracks(count: usize, size: f32, spacing: f32, offset: f32, extent: f32) -> Range<usize> {
+    let pitch = size + spacing;
+    if count == 0 || size <= 0.0 || pitch <= 0.0 || extent <= 0.0 {
+        return 0..0;
+    }
+
+    let offset = if offset.is_finite() { offset } else { 0.0 };
+    let end = offset + extent;
+    if end <= 0.0 {
+        return 0..0;
+    }
+
+    let start = offset.max(0.0);
+    let mut first = (start / pitch).floor() as usize;
+    if start - first as f32 * pitch >= size {
+        // the view starts in the spacing after the track
+        first += 1;
+    }
+    let last = (end / pitch).ceil() as usize;
+
+    let first = first.min(count);
+    first..last.clamp(first, count)
+}
+
+/// Returns the relative offset that brings the track starting at `start`
+/// and of `size` to the `align`ment in `extent` units of `content`, scrolled
+/// by `offset` units.
+fn align_track(start: f32, size: f32, offset: f32, extent: f32, content: f32, align: Align) -> f32 {
+    let scrollable = content - extent;
+    if scrollable <= 0.0 || !scrollable.is_finite() {
+        return 0.0;
+    }
+
+    let end = start + size;
+    let target = match align {
+        Align::Start => start,
+        Align::Center => start + (size - extent) / 2.0,
+        Align::End => end - extent,
+        // a track larger than the view shows its start
+        Align::Nearest if start < offset || size > extent => start,
+        Align::Nearest if end > offset + extent => end - extent,
+        Align::Nearest => offset,
+    };
+
+    (target / scrollable).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scrolled(layout: &TileLayout, columns: usize, y: f32, size: Size) -> VisibleTiles {
+        let content = layout.content_size(columns);
+        let viewport = Viewport::from_relative(RelativeOffset { x: 0.0, y }, size, content);
+        layout.visible(columns, viewport)
+    }
+
+    #[test]
+    fn visible_rows_while_scrolling() {
+        let layout = TileLayout::new(100, Size::new(128.0, 128.0));
+        let size = Size::new(128.0, 598.0);
+
+        let tests = vec![(0.0, 0..5), (0.5, 47..53), (1.0, 95..100)];
+
+        for (y, expected) in tests {
+            assert_eq!(scrolled(&layout, 1, y, size).rows, expected, "y: {y}");
+        }
+    }
+
+    #[test]
+    fn partial_last_row_is_visible() {
+        let layout = TileLayout::new(10, Size::new(100.0, 100.0));
+
+        let visible = scrolled(&layout, 3, 1.0, Size::new(300.0, 250.0));
+
+        assert_eq!(visible.rows, 1..4);
+        assert_eq!(visible.range(), 3..10);
+        assert!(visible.contains(9));
+        assert!(!visible.contains(10));
+    }
+
+    #[test]
+    fn no_columns_in_narrow_window() {
+        let layout = TileLayout::new(10, Size::new(100.0, 100.0));
+
+        assert_eq!(layout.columns(99.0), 0);
+        assert_eq!(layout.rows(0), 0);
+
+        let visible = scrolled(&layout, 0, 0.5, Size::new(99.0, 500.0));
+        assert!(visible.is_empty());
+        assert_eq!(visible.range(), 0..0);
+        assert!(!visible.contains(0));
+    }
+
+    #[test]
+    fn empty_or_zero_sized_window() {
+        let layout = TileLayout::new(10, Size::new(100.0, 100.0));
+
+        assert!(scrolled(&layout, 2, 0.0, Size::ZERO).is_empty());
+        assert!(scrolled(
+            &TileLayout::new(0, Size::new(100.0, 100.0)),
+            2,
+            0.0,
+            Size::new(500.0, 500.0)
+        )
+        .is_empty());
+        assert!(scrolled(
+            &TileLayout::new(10, Size::ZERO),
+            2,
+            0.0,
+            Size::new(500.0, 500.0)
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn content_smaller_than_viewport() {
+        let layout = TileLayout::new(4, Size::new(100.0, 100.0));
+
+        let visible = scrolled(&layout, 2, 1.0, Size::new(200.0, 1000.0));
+
+        assert_eq!(visible.rows, 0..2);
+        assert_eq!(visible.range(), 0..4);
+    }
+
+    #[test]
+    fn columns_account_for_spacing_and_padding() {
+        let layout = TileLayout {
+            padding: Padding::new(20),
+            ..TileLayout::new(10, Size::new(100.0, 100.0)).spacing(10.0)
+        };
+
+        assert_eq!(layout.columns(350.0), 2);
+        assert_eq!(layout.columns(360.0), 3);
+        assert_eq!(layout.content_size(3), Size::new(360.0, 470.0));
+        assert_eq!(layout.position(4, 3), Vector::new(130.0, 130.0));
+    }
+
+    #[test]
+    fn view_in_spacing_skips_track() {
+        let layout = TileLayout {
+            padding: Padding::new(10),
+            ..TileLayout::new(10, Size::new(100.0, 100.0)).spacing(20.0)
+        };
+
+        let viewport = Viewport::new(Vector::new(0.0, 115.0), Size::new(100.0, 100.0));
+
+        assert_eq!(layout.visible(1, viewport).rows, 1..2);
+    }
+
+    #[test]
+    fn horizontal_offset_selects_columns() {
+        let layout = TileLayout::new(20, Size::new(100.0, 100.0));
+
+        let viewport = Viewport::new(Vector::new(150.0, 0.0), Size::new(200.0, 100.0));
+        let visible = layout.visible(5, viewport);
+
+        assert_eq!(visible.columns, 1..4);
+        assert!(visible.contains(3));
+        assert!(!visible.contains(4));
+        assert_eq!(visible.range(), 0..5);
+    }
+
+    #[test]
+    fn overscan_stays_in_bounds() {
+        let layout = TileLayout::new(14, Size::new(100.0, 100.0));
+        let viewport = Viewport::new(Vector::new(0.0, 250.0), Size::new(200.0, 100.0));
+
+        let visible = layout.visible(2, viewport);
+        assert_eq!(visible.rows, 2..4);
+
+        let overscanned = visible.with_overscan(3, 1);
+        assert_eq!(overscanned.rows, 0..7);
+        assert_eq!(overscanned.columns, 0..2);
+        assert_eq!(overscanned.range(), 0..14);
+    }
+
+    #[test]
+    fn bounds_cover_tiles_without_spacing() {
+        let layout = TileLayout::new(20, Size::new(10.0, 10.0)).spacing(5.0);
+        let viewport = Viewport::new(Vector::new(0.0, 20.0), Size::new(100.0, 20.0));
+        let tiles = layout.visible(4, viewport);
+
+        assert_eq!(tiles.rows, 1..3);
+        assert_eq!(
+            layout.bounds(&tiles),
+            Rectangle::new(Point::new(0.0, 15.0), Size::new(55.0, 25.0))
+        );
+    }
+
+    #[test]
+    fn offset_of_aligns_tile_in_view() {
+        let layout = TileLayout::new(100, Size::new(100.0, 100.0)).spacing(20.0);
+        let size = Size::new(100.0, 350.0);
+        let content = layout.content_size(1);
+        let top = Viewport::new(Vector::new(0.0, 0.0), size);
+
+        let scroll_to = |index: usize, from: Viewport, align: Align| {
+            let offset = layout.offset_of(index, 1, from, align);
+            Viewport::from_relative(offset, size, content).offset.y
+        };
+
+        assert!((scroll_to(50, top, Align::Start) - 6000.0).abs() < 0.01);
+        assert!((scroll_to(50, top, Align::End) - 5750.0).abs() < 0.01);
+        assert!((scroll_to(50, top, Align::Center) - 5875.0).abs() < 0.01);
+        assert!((scroll_to(99, top, Align::Start) - (content.height - 350.0)).abs() < 0.01);
+        assert!(scroll_to(0, top, Align::End).abs() < 0.01);
+    }
+
+    #[test]
+    fn offset_of_nearest_scrolls_least() {
+        let layout = TileLayout::new(100, Size::new(100.0, 100.0)).spacing(20.0);
+        let size = Size::new(100.0, 350.0);
+        let content = layout.content_size(1);
+        let viewport = Viewport::new(Vector::new(0.0, 1200.0), size);
+
+        let scroll_to = |index: usize| {
+            let offset = layout.offset_of(index, 1, viewport, Align::Nearest);
+            Viewport::from_relative(offset, size, content).offset.y
+        };
+
+        assert!((scroll_to(11) - 1200.0).abs() < 0.01);
+        assert!((scroll_to(5) - 600.0).abs() < 0.01);
+        assert!((scroll_to(20) - 2150.0).abs() < 0.01);
+        assert!((scroll_to(100) - 1200.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn offset_to_tiles_of_any_size() {
+        let content = Size::new(400.0, 2000.0);
+        let viewport = Viewport::new(Vector::new(0.0, 500.0), Size::new(400.0, 300.0));
+        let scroll_to = |tile: Rectangle, align: Align| {
+            let offset = viewport.offset_to(tile, content, align);
+            Viewport::from_relative(offset, viewport.size, content)
+                .offset
+                .y
+        };
+
+        let tall = Rectangle::new(Point::new(150.0, 1000.0), Size::new(50.0, 200.0));
+        assert!((scroll_to(tall, Align::Start) - 1000.0).abs() < 0.01);
+        assert!((scroll_to(tall, Align::Center) - 950.0).abs() < 0.01);
+        assert!((scroll_to(tall, Align::Nearest) - 900.0).abs() < 0.01);
+
+        let in_view = Rectangle::new(Point::new(0.0, 600.0), Size::new(200.0, 80.0));
+        assert!((scroll_to(in_view, Align::Nearest) - 500.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn reanchor_keeps_tile_in_place() {
+        let tile = Size::new(100.0, 100.0);
+        let viewport = Viewport::new(Vector::new(0.0, 1030.0), Size::new(100.0, 300.0));
+
+        let layout = TileLayout::new(100, tile);
+        let offset = Vector::new(0.0, 30.0);
+        assert_eq!(layout.anchor(1, viewport), Some((10, offset)));
+        assert_eq!(layout.reanchor(1, viewport, 10, offset), None);
+
+        // five tiles were inserted above
+        let layout = TileLayout::new(105, tile);
+        let offset = layout.reanchor(1, viewport, 15, offset).unwrap();
+        let snapped = Viewport::from_relative(offset, viewport.size, layout.content_size(1));
+        assert!((snapped.offset.y - 1530.0).abs() < 0.5);
+    }
+
+    #[test]
+    fn anchor_on_first_tile_in_view() {
+        let viewport = Viewport::new(Vector::new(0.0, 250.0), Size::new(300.0, 200.0));
+        let tiles = [
+            Rectangle::new(Point::new(0.0, 0.0), Size::new(300.0, 240.0)),
+            Rectangle::new(Point::new(0.0, 248.0), Size::new(150.0, 100.0)),
+            Rectangle::new(Point::new(158.0, 248.0), Size::new(142.0, 100.0)),
+        ];
+
+        assert_eq!(viewport.anchor(&tiles), Some((1, Vector::new(0.0, 2.0))));
+        assert_eq!(viewport.anchor(&tiles[..1]), None);
+
+        // a tile was inserted above, pushing the anchor down a row
+        let offset = viewport
+            .reanchor(
+                Size::new(300.0, 1000.0),
+                Vector::new(0.0, 356.0),
+                Vector::new(0.0, 2.0),
+            )
+            .unwrap();
+        let snapped = Viewport::from_relative(offset, viewport.size, Size::new(300.0, 1000.0));
+        assert!((snapped.offset.y - 358.0).abs() < 0.5);
+    }
+
+    #[test]
+    fn reanchor_past_the_end_is_not_repeated() {
+        let layout = TileLayout::new(10, Size::new(100.0, 100.0));
+        let viewport = Viewport::new(Vector::new(0.0, 700.0), Size::new(100.0, 300.0));
+
+        assert_eq!(layout.reanchor(1, viewport, 9, Vector::new(0.0, 0.0)), None);
+        assert_eq!(layout.anchor(0, viewport), None);
+    }
+
+    #[test]
+    fn columns_culled_in_wide_grid() {
+        let layout = TileLayout::new(95, Size::new(100.0, 100.0));
+        let viewport = Viewport::new(Vector::new(250.0, 850.0), Size::new(200.0, 150.0));
+
+        let visible = layout.visible(10, viewport);
+        assert_eq!(visible.rows, 8..10);
+        assert_eq!(visible.columns, 2..5);
+        assert_eq!(
+            visible.indices().collect::<Vec<_>>(),
+            vec![82, 83, 84, 92, 93, 94]
+        );
+        assert_eq!(
+            layout.anchor(10, viewport),
+            Some((82, Vector::new(50.0, 50.0)))
+        );
+    }
+
+    #[test]
+    fn single_row_scrolls_horizontally() {
+        let layout = TileLayout::new(50, Size::new(100.0, 100.0)).spacing(10.0);
+        let content = layout.content_size(50);
+        assert_eq!(layout.rows(50), 1);
+
+        let viewport = Viewport::from_relative(
+            RelativeOffset { x: 0.5, y: 0.0 },
+            Size::new(500.0, 100.0),
+            content,
+        );
+        let visible = layout.visible(50, viewport);
+
+        assert_eq!(visible.rows, 0..1);
+        assert_eq!(visible.columns, 22..28);
+        assert_eq!(
+            visible.with_overscan(2, 2).indices().collect::<Vec<_>>(),
+            (20..30).collect::<Vec<_>>()
+        );
+    }
+}
@@ -1,13 +1,16 @@
 use iced::{
     alignment::{Horizontal, Vertical},
     widget::{Column, Container, Text},
-    Application, Command, Element, Length, Settings, Theme,
+    Application, Command, Element, Length, Settings, Subscription, Theme,
 };
 use iced_aw::{TabBarPosition, TabLabel, Tabs};
 
 mod checklist;
+mod data_grid;
 mod grid;
 mod nested_list;
+#[cfg(test)]
+mod testing;
 mod tile_pane;
 mod todo;
 // mod selection_tree;
@@ -84,6 +87,10 @@ impl Application for Example {
         }
     }
 
+    fn subscription(&self) -> Subscription<Self::Message> {
+        self.tile_tab.subscription().map(Self::Message::TilePane)
+    }
+
     fn view(&self) -> iced::Element<'_, Self::Message> {
         let tabs = Tabs::new(self.active_tab, Message::TabSelected)
             .push(
@@ -0,0 +1,264 @@
+//! Lay out widgets in plain `cargo test`, without a window or a GPU.
+//!
+//! [`Renderer`](Renderer) draws nothing and measures text with a fixed
+//! character width, and [`Stub`](Stub) is an element of a given size, so the
+//! position of every cell of a layout can be known in advance.
+use iced_native::{
+    layout::{Limits, Node},
+    renderer,
+    text::{self, Hit, Text},
+    widget::{Tree, Widget},
+    Background, Element, Font, Layout, Length, Point, Rectangle, Size, Vector,
+};
+
+/// How far two coordinates can be apart and still be equal.
+const EPSILON: f32 = 0.01;
+
+/// A renderer that draws nothing.
+///
+/// Every character of a text is half as wide as the text size and every line
+/// is as high as the text size. Lines wrap at the width available.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Renderer;
+
+impl iced_native::Renderer for Renderer {
+    type Theme = iced::Theme;
+
+    fn with_layer(&mut self, _bounds: Rectangle, f: impl FnOnce(&mut Self)) {
+        f(self);
+    }
+
+    fn with_translation(&mut self, _translation: Vector, f: impl FnOnce(&mut Self)) {
+        f(self);
+    }
+
+    fn clear(&mut self) {}
+
+    fn fill_quad(&mut self, _quad: renderer::Quad, _background: impl Into<Background>) {}
+}
+
+impl text::Renderer for Renderer {
+    type Font = Font;
+
+    const ICON_FONT: Font = Font::Default;
+    const CHECKMARK_ICON: char = '0';
+    const ARROW_DOWN_ICON: char = '0';
+
+    fn default_size(&self) -> u16 {
+        20
+    }
+
+    fn measure(&self, content: &str, size: u16, _font: Font, bounds: Size) -> (f32, f32) {
+        measure_text(content, f32::from(size), bounds)
+    }
+
+    fn hit_test(
+        &self,
+        _contents: &str,
+        _size: f32,
+        _font: Font,
+        _bounds: Size,
+        _point: Point,
+        _nearest_only: bool,
+    ) -> Option<Hit> {
+        None
+    }
+
+    fn fill_text(&mut self, _text: Text<'_, Font>) {}
+}
+
+/// Returns the width and height of `content` written at `size` in `bounds`,
+/// with characters half as wide as `size`.
+#[allow(clippy::cast_precision_loss)]
+fn measure_text(content: &str, size: f32, bounds: Size) -> (f32, f32) {
+    let char_width = size / 2.0;
+    let per_line = if bounds.width.is_finite() && char_width > 0.0 {
+        ((bounds.width / char_width).floor() as usize).max(1)
+    } else {
+        usize::MAX
+    };
+
+    let (width, lines) = content
+        .split('\n')
+        .map(|line| {
+            let chars = line.chars().count();
+            (chars.min(per_line), chars.div_ceil(per_line).max(1))
+        })
+        .fold((0, 0), |(width, lines), (chars, wrapped)| {
+            (width.max(chars), lines + wrapped)
+        });
+
+    (width as f32 * char_width, lines as f32 * size)
+}
+
+/// An element that takes the space given by its width and height and draws
+/// nothing.
+#[derive(Debug, Clone, Copy)]
+pub struct Stub {
+    width: Length,
+    height: Length,
+}
+
+impl Stub {
+    /// Creates a new [`Stub`](Stub) of the given `width` and `height`.
+    pub fn new(width: impl Into<Length>, height: impl Into<Length>) -> Self {
+        Self {
+            width: width.into(),
+            height: height.into(),
+        }
+    }
+}
+
+/// Creates a new [`Stub`](Stub) of `width` by `height` units.
+pub fn stub(width: u16, height: u16) -> Stub {
+    Stub::new(width, height)
+}
+
+impl<Message> Widget<Message, Renderer> for Stub {
+    fn width(&self) -> Length {
+        self.width
+    }
+
+    fn height(&self) -> Length {
+        self.height
+    }
+
+    fn layout(&self, _renderer: &Renderer, limits: &Limits) -> Node {
+        Node::new(
+            limits
+                .width(self.width)
+                .height(self.height)
+                .resolve(Size::ZERO),
+        )
+    }
+
+    fn draw(
+        &self,
+        _state: &Tree,
+        _renderer: &mut Renderer,
+        _theme: &iced::Theme,
+        _style: &renderer::Style,
+        _layout: Layout<'_>,
+        _cursor_position: Point,
+        _viewport: &Rectangle,
+    ) {
+    }
+}
+
+impl<'a, Message> From<Stub> for Element<'a, Message, Renderer> {
+    fn from(stub: Stub) -> Self {
+        Element::new(stub)
+    }
+}
+
+/// Lays out the `element` in a window of the given `size`, after building
+/// its tree as the runtime would.
+pub fn layout<'a, Message>(element: impl Into<Element<'a, Message, Renderer>>, size: Size) -> Node {
+    let element = element.into();
+    let mut tree = Tree::new(&element);
+    element.as_widget().diff(&mut tree);
+
+    element
+        .as_widget()
+        .layout(&Renderer, &Limits::new(Size::ZERO, size))
+}
+
+/// Creates a [`Rectangle`](Rectangle) at `x`, `y` of `width` by `height`.
+pub fn rect(x: f32, y: f32, width: f32, height: f32) -> Rectangle {
+    Rectangle {
+        x,
+        y,
+        width,
+        height,
+    }
+}
+
+/// Asserts that the child of `node` at `index` has the `expected` bounds,
+/// relative to `node`.
+#[track_caller]
+pub fn assert_cell_bounds(node: &Node, index: usize, expected: Rectangle) {
+    let Some(cell) = node.children().get(index) else {
+        panic!(
+            "no cell {index}, the node has {} children",
+            node.children().len()
+        );
+    };
+
+    let bounds = cell.bounds();
+    assert!(
+        approx_eq(bounds, expected),
+        "cell {index} has bounds {bounds:?}, expected {expected:?}"
+    );
+}
+
+/// Asserts that the children of `node` have the `expected` bounds, in order.
+#[track_caller]
+pub fn assert_cells(node: &Node, expected: &[Rectangle]) {
+    assert_eq!(
+        node.children().len(),
+        expected.len(),
+        "the node has {} children, expected {}",
+        node.children().len(),
+        expected.len()
+    );
+
+    for (index, &bounds) in expected.iter().enumerate() {
+        assert_cell_bounds(node, index, bounds);
+    }
+}
+
+/// Asserts that `node` has the `expected` size.
+#[track_caller]
+pub fn assert_size(node: &Node, expected: Size) {
+    let size = node.size();
+    assert!(
+        (size.width - expected.width).abs() < EPSILON
+            && (size.height - expected.height).abs() < EPSILON,
+        "node has size {size:?}, expected {expected:?}"
+    );
+}
+
+/// Returns whether the two rectangles are equal, up to rounding errors.
+fn approx_eq(a: Rectangle, b: Rectangle) -> bool {
+    [
+        (a.x, b.x),
+        (a.y, b.y),
+        (a.width, b.width),
+        (a.height, b.height),
+    ]
+    .iter()
+    .all(|(a, b)| (a - b).abs() < EPSILON)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measure_text_by_characters() {
+        let unbounded = Size::new(f32::INFINITY, f32::INFINITY);
+
+        assert_eq!(measure_text("abcd", 20.0, unbounded), (40.0, 20.0));
+        assert_eq!(measure_text("ab\nabcdef", 10.0, unbounded), (30.0, 20.0));
+        assert_eq!(measure_text("", 10.0, unbounded), (0.0, 10.0));
+    }
+
+    #[test]
+    fn measure_text_wraps_lines() {
+        let narrow = Size::new(25.0, f32::INFINITY);
+
+        assert_eq!(measure_text("abcdefg", 10.0, narrow), (25.0, 20.0));
+    }
+
+    #[test]
+    fn stub_resolves_length_in_limits() {
+        let size = Size::new(100.0, 50.0);
+
+        assert_size(&layout::<()>(stub(30, 20), size), Size::new(30.0, 20.0));
+        assert_size(
+            &layout::<()>(Stub::new(Length::Fill, 10), size),
+            Size::new(100.0, 10.0),
+        );
+        assert_size(&layout::<()>(stub(300, 20), size), Size::new(100.0, 20.0));
+    }
+}
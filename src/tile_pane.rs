@@ -1,15 +1,27 @@
 use once_cell::sync::Lazy;
 use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
 use iced::widget::scrollable;
 use iced::{
-    widget::{button, column, container, image, scrollable::RelativeOffset, slider, text},
-    Alignment, Command, Element, Length,
+    subscription,
+    widget::{button, column, container, image, row, scrollable::RelativeOffset, slider, text},
+    window, Alignment, Command, Element, Event, Length, Renderer, Size, Subscription, Vector,
+};
+use iced_native::{
+    event,
+    layout::{Limits, Node},
+    mouse, renderer,
+    widget::{tree, Operation, Tree, Widget},
+    Clipboard, Layout, Point, Rectangle, Shell,
 };
 
 use crate::Tab;
 
-use crate::grid::Grid;
+use crate::lazy_scroll::{Align, Viewport, VirtualGrid};
+
+use crate::data_grid::{self, CellEdited, DataGrid, Value};
+use crate::grid::{Grid, Reorder, Track};
 
 pub struct TilePane {
     tile_pane: ImageTiles,
@@ -46,8 +58,17 @@ impl TilePane {
         match self.tile_pane.update(message) {
             ScrollCommand::None => Command::<Message>::none(),
             ScrollCommand::ScrollToStart { id, offset } => scrollable::snap_to(id, offset),
+            ScrollCommand::SnapTo { id, offset } => scrollable::snap_to(id, offset),
+            ScrollCommand::ScrollToIndex { id, index, align } => self
+                .tile_pane
+                .scroll_to_index(index, align)
+                .map_or_else(Command::none, |offset| scrollable::snap_to(id, offset)),
         }
     }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        self.tile_pane.subscription()
+    }
 }
 
 impl Tab for TilePane {
@@ -75,13 +96,29 @@ impl Tab for TilePane {
 }
 
 const DEFAULT_TILE_SIZE: u16 = 128;
+const TILE_SPACING: u16 = 8;
+const FILMSTRIP_TILE_SIZE: u16 = 96;
+const REFLOW_DURATION: Duration = Duration::from_millis(300);
+/// The editable columns of the metadata table, by index.
+const CAPTION_COLUMN: usize = 3;
+const RATING_COLUMN: usize = 4;
 static SCROLLABLE_ID: Lazy<scrollable::Id> = Lazy::new(scrollable::Id::unique);
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum Message {
     ScrollToStart,
+    ScrollToSelection,
     Scrolled(scrollable::RelativeOffset),
     ZoomChanged(u16),
+    SelectionChanged(Vec<usize>),
+    Reordered(Reorder),
+    Resized,
+    Tick(Instant),
+    Measured(Geometry),
+    MetadataEdited(CellEdited),
+    Opened(usize),
+    FilmstripJumped,
+    Closed,
 }
 
 #[derive(Debug, Clone)]
@@ -91,12 +128,41 @@ pub enum ScrollCommand {
         id: scrollable::Id,
         offset: RelativeOffset,
     },
+    /// Scrolls to the offset, which the scrollable does not know of yet.
+    SnapTo {
+        id: scrollable::Id,
+        offset: RelativeOffset,
+    },
+    /// Scrolls to the image at `index`, showing it where `align` says.
+    ScrollToIndex {
+        id: scrollable::Id,
+        index: usize,
+        align: Align,
+    },
 }
 
 pub struct ImageTiles {
     tile_size: u16,
     scroll_offset: scrollable::RelativeOffset,
     images: Vec<ImageTile>,
+    /// The uids of the images selected.
+    selected: Vec<u32>,
+    reflow_until: Option<Instant>,
+    /// The version of the images and how they are laid out, bumped whenever
+    /// they change.
+    version: u64,
+    /// Where the images were laid out, as last drawn.
+    geometry: Geometry,
+    /// The uid of the image to scroll to once the images are laid out as
+    /// they are now, and where to show it.
+    scroll_to: Option<(u32, Align)>,
+    /// The uid of the image to keep in place once the images are laid out
+    /// as they are now, and how far the view was scrolled past it.
+    anchor: Option<(u32, Vector)>,
+    /// The uid of the image shown on its own, if any.
+    viewing: Option<u32>,
+    /// Whether the filmstrip has yet to be scrolled to the image shown.
+    filmstrip_jump: bool,
 }
 
 impl ImageTiles {
@@ -109,6 +175,119 @@ impl ImageTiles {
             tile_size: DEFAULT_TILE_SIZE,
             scroll_offset: scrollable::RelativeOffset::START,
             images,
+            selected: Vec::new(),
+            reflow_until: None,
+            version: 0,
+            geometry: Geometry {
+                version: 0,
+                offset: Vector::new(0.0, 0.0),
+                viewport: Size::ZERO,
+                content: Size::ZERO,
+                tiles: Vec::new(),
+            },
+            scroll_to: None,
+            anchor: None,
+            viewing: None,
+            filmstrip_jump: false,
+        }
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        let resized = subscription::events_with(|event, _status| match event {
+            Event::Window(window::Event::Resized { .. }) => Some(Message::Resized),
+            _ => None,
+        });
+
+        if self.reflow_until.is_some() {
+            Subscription::batch([resized, window::frames().map(Message::Tick)])
+        } else {
+            resized
+        }
+    }
+
+    fn start_reflow(&mut self) {
+        self.reflow_until = Some(Instant::now() + REFLOW_DURATION);
+        // the images move, so the geometry measured so far is out of date
+        self.version += 1;
+    }
+
+    /// Returns the offset that scrolls to the image at `index`, shown where
+    /// `align` says, and scrolls there.
+    ///
+    /// Returns `None` if the images have changed since they were measured.
+    pub fn scroll_to_index(&mut self, index: usize, align: Align) -> Option<RelativeOffset> {
+        let viewport = self.viewport()?;
+        let geometry = &self.geometry;
+        let offset = viewport.offset_to(*geometry.tiles.get(index)?, geometry.content, align);
+        self.scroll_offset = offset;
+
+        Some(offset)
+    }
+
+    /// Returns the part of the images in view, if they have not changed
+    /// since they were measured.
+    fn viewport(&self) -> Option<Viewport> {
+        let geometry = &self.geometry;
+
+        (geometry.version == self.version)
+            .then(|| Viewport::new(geometry.offset, geometry.viewport))
+    }
+
+    /// Returns the scroll to the image waiting to be scrolled to, once the
+    /// images are measured as they are now.
+    fn scroll_when_measured(&mut self) -> ScrollCommand {
+        if self.viewport().is_none() {
+            return ScrollCommand::None;
+        }
+
+        let index = self.scroll_to.take().and_then(|(uid, align)| {
+            Some((self.images.iter().position(|img| img.uid == uid)?, align))
+        });
+        match index {
+            Some((index, align)) => ScrollCommand::ScrollToIndex {
+                id: SCROLLABLE_ID.clone(),
+                index,
+                align,
+            },
+            None => ScrollCommand::None,
+        }
+    }
+
+    /// Keeps the first image in view in place once the images are measured
+    /// again, so changing the images above it does not make it jump.
+    fn anchor_first_in_view(&mut self) {
+        // an anchor still waiting for the images to be measured is kept
+        if self.anchor.is_none() {
+            self.anchor = self
+                .viewport()
+                .and_then(|viewport| viewport.anchor(&self.geometry.tiles))
+                .and_then(|(index, offset)| Some((self.images.get(index)?.uid, offset)));
+        }
+    }
+
+    /// Returns the scroll that brings the anchored image back in place, once
+    /// the images are measured as they are now.
+    fn reanchor_when_measured(&mut self) -> ScrollCommand {
+        let Some(viewport) = self.viewport() else {
+            return ScrollCommand::None;
+        };
+
+        let geometry = &self.geometry;
+        let offset = self.anchor.take().and_then(|(uid, offset)| {
+            let index = self.images.iter().position(|img| img.uid == uid)?;
+            let tile = geometry.tiles.get(index)?;
+            viewport.reanchor(geometry.content, Vector::new(tile.x, tile.y), offset)
+        });
+
+        match offset {
+            Some(offset) => {
+                self.scroll_offset = offset;
+                ScrollCommand::SnapTo {
+                    id: SCROLLABLE_ID.clone(),
+                    offset,
+                }
+            }
+            None => ScrollCommand::None,
         }
     }
 
@@ -121,18 +300,88 @@ impl ImageTiles {
                     offset: self.scroll_offset,
                 }
             }
+            Message::ScrollToSelection => {
+                self.scroll_to = self.selected.first().map(|&uid| (uid, Align::Nearest));
+                self.scroll_when_measured()
+            }
             Message::Scrolled(offset) => {
                 self.scroll_offset = offset;
                 ScrollCommand::None
             }
             Message::ZoomChanged(zoom) => {
                 self.tile_size = zoom;
+                self.start_reflow();
+                ScrollCommand::None
+            }
+            Message::SelectionChanged(selected) => {
+                self.selected = selected
+                    .into_iter()
+                    .filter_map(|index| self.images.get(index))
+                    .map(|img| img.uid)
+                    .collect();
+                ScrollCommand::None
+            }
+            Message::Reordered(reorder) => {
+                self.anchor_first_in_view();
+                reorder.apply(&mut self.images);
+                self.start_reflow();
+                ScrollCommand::None
+            }
+            Message::Resized => {
+                self.start_reflow();
+                ScrollCommand::None
+            }
+            Message::MetadataEdited(CellEdited { row, column, value }) => {
+                if let Some(img) = self.images.get_mut(row) {
+                    match (column, value) {
+                        (CAPTION_COLUMN, Value::Text(caption)) => img.caption = caption,
+                        (RATING_COLUMN, Value::Integer(rating)) => {
+                            img.rating = rating.clamp(0, 5);
+                        }
+                        _ => {}
+                    }
+                }
+                ScrollCommand::None
+            }
+            Message::Opened(index) => {
+                self.viewing = self.images.get(index).map(|img| img.uid);
+                self.filmstrip_jump = true;
+                ScrollCommand::None
+            }
+            Message::FilmstripJumped => {
+                self.filmstrip_jump = false;
+                ScrollCommand::None
+            }
+            Message::Closed => {
+                self.viewing = None;
+                ScrollCommand::None
+            }
+            Message::Measured(geometry) => {
+                self.geometry = geometry;
+                // the image asked for is scrolled to wherever the anchor is
+                let reanchor = self.reanchor_when_measured();
+                match self.scroll_when_measured() {
+                    ScrollCommand::None => reanchor,
+                    scroll => scroll,
+                }
+            }
+            Message::Tick(now) => {
+                if self.reflow_until.is_some_and(|until| now >= until) {
+                    self.reflow_until = None;
+                }
                 ScrollCommand::None
             }
         }
     }
 
     pub fn view(&self) -> iced::Element<'_, Message> {
+        let viewing = self
+            .viewing
+            .and_then(|uid| self.images.iter().position(|img| img.uid == uid));
+        if let Some(index) = viewing {
+            return self.view_image(index);
+        }
+
         let zoom_slider = slider(50..=512, self.tile_size, Message::ZoomChanged);
 
         let scroll_to_beginning = || {
@@ -141,16 +390,23 @@ impl ImageTiles {
                 .on_press(Message::ScrollToStart)
         };
 
-        let scrollable_content: Element<Message> = Element::from(
-            scrollable(
+        let scrollable_content: Element<Message> = Element::from(Measure {
+            content: scrollable(
                 column!(
                     Grid::with_children(
                         self.images
                             .iter()
-                            .map(|img| img.view(Length::Units(self.tile_size)))
+                            .map(|img| img.view(Length::Shrink))
                             .collect()
                     )
-                    .column_width(self.tile_size),
+                    .keys(self.images.iter().map(ImageTile::layout_key))
+                    .justified_rows(self.tile_size)
+                    .animate_reflow(REFLOW_DURATION)
+                    .spacing(TILE_SPACING)
+                    .on_selection_changed(Message::SelectionChanged)
+                    .on_reorder(Message::Reordered)
+                    .on_activate(Message::Opened),
+                    metadata_table(&self.images),
                     scroll_to_beginning()
                 )
                 .width(Length::Fill)
@@ -161,30 +417,343 @@ impl ImageTiles {
             .height(Length::Fill)
             .vertical_scroll(theming::scrollbar_properties())
             .id(SCROLLABLE_ID.clone())
-            .on_scroll(Message::Scrolled),
+            .on_scroll(Message::Scrolled)
+            .into(),
+            version: self.version,
+        });
+
+        let selected = text(format!("{} selected", self.selected.len()));
+        let scroll_to_selection = button("Scroll to selection")
+            .on_press(Message::ScrollToSelection)
+            .padding(5);
+
+        column!(
+            scrollable_content,
+            row!(zoom_slider, selected, scroll_to_selection)
+                .spacing(10)
+                .align_items(Alignment::Center)
+        )
+        .spacing(10)
+        .into()
+    }
+
+    /// Shows the image at `index` on its own, with a filmstrip of all the
+    /// images under it.
+    fn view_image(&self, index: usize) -> Element<'_, Message> {
+        let mut filmstrip = VirtualGrid::new(self.images.len(), |i| {
+            button(self.images[i].view(Length::Fill))
+                .on_press(Message::Opened(i))
+                .padding(2)
+                .into()
+        })
+        .filmstrip()
+        .tile_size(FILMSTRIP_TILE_SIZE)
+        .spacing(TILE_SPACING)
+        .on_jump(|_| Message::FilmstripJumped)
+        .anchored(
+            |i| u64::from(self.images[i].uid),
+            |uid| self.images.iter().position(|img| u64::from(img.uid) == uid),
         );
+        if self.filmstrip_jump {
+            filmstrip = filmstrip.scroll_to(index, Align::Nearest);
+        }
+
+        let back = button("Back to all images")
+            .padding(10)
+            .on_press(Message::Closed);
 
-        column!(scrollable_content, zoom_slider).spacing(10).into()
+        column!(
+            self.images[index].view(Length::Fill),
+            container(filmstrip)
+                .width(Length::Fill)
+                // room for the scrollbar under the tiles
+                .height(Length::Units(FILMSTRIP_TILE_SIZE + 20)),
+            back
+        )
+        .spacing(10)
+        .align_items(Alignment::Center)
+        .into()
+    }
+}
+
+fn metadata_table(images: &[ImageTile]) -> Element<'_, Message> {
+    let columns = vec![
+        data_grid::Column::new("File", |_, img: &ImageTile| text(img.file_name()).into())
+            .width(Track::Fill)
+            .sort_by_key(ImageTile::file_name),
+        data_grid::Column::new("Type", |_, img: &ImageTile| text(img.extension()).into())
+            .sort_by_key(ImageTile::extension),
+        data_grid::Column::new("Size", |_, img: &ImageTile| {
+            let size = img.file_size.map_or("-".into(), |bytes| {
+                format!("{:.1} KiB", bytes as f64 / 1024.0)
+            });
+            text(size).into()
+        })
+        .sort_by_key(|img: &ImageTile| img.file_size),
+        data_grid::Column::new("Caption", |_, img: &ImageTile| text(&img.caption).into())
+            .width(Track::Fill)
+            .sort_by_key(|img: &ImageTile| img.caption.to_lowercase())
+            .editable(|img: &ImageTile| Value::Text(img.caption.clone())),
+        data_grid::Column::new("Rating", |_, img: &ImageTile| {
+            text("★".repeat(usize::try_from(img.rating).unwrap_or(0))).into()
+        })
+        .sort_by_key(|img: &ImageTile| img.rating)
+        .editable(|img: &ImageTile| Value::Integer(img.rating)),
+    ];
+
+    DataGrid::new(columns, images)
+        .spacing(10)
+        .on_cell_edited(Message::MetadataEdited)
+        .into()
+}
+
+/// Where the images are laid out in the scrollable, as measured by
+/// [`Measure`](Measure).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Geometry {
+    /// The version of the images measured.
+    version: u64,
+    /// How far the scrollable is scrolled.
+    ///
+    /// Scrolling with the mouse keeps this offset when the content grows,
+    /// unlike the relative offset last reported.
+    offset: Vector,
+    /// The size of the scrollable.
+    viewport: Size,
+    /// The size of its content.
+    content: Size,
+    /// The bounds of the images, relative to the content.
+    tiles: Vec<Rectangle>,
+}
+
+/// Wraps the scrollable of the images to report where they are laid out,
+/// whenever that changes.
+///
+/// The images are measured before every frame is drawn, once the events of
+/// the frame have been handled.
+struct Measure<'a> {
+    /// The scrollable, with the grid of images first in its column.
+    content: Element<'a, Message>,
+    /// The version of the images in the grid.
+    version: u64,
+}
+
+impl<'a> Measure<'a> {
+    /// Returns where the images are laid out in the `layout` of the
+    /// scrollable, scrolled as its `state` says, unless it is the `reported`
+    /// geometry.
+    fn measure(
+        &self,
+        reported: Option<&Geometry>,
+        state: &iced_native::widget::scrollable::State,
+        layout: Layout<'_>,
+    ) -> Option<Geometry> {
+        let content = layout.children().next();
+        let origin = content.map_or(Point::ORIGIN, |content| content.position());
+        let tiles = || {
+            content
+                .and_then(|content| content.children().next())
+                .into_iter()
+                .flat_map(Layout::children)
+                .map(move |tile| {
+                    let bounds = tile.bounds();
+                    Rectangle::new(Point::ORIGIN + (bounds.position() - origin), bounds.size())
+                })
+        };
+
+        let version = self.version;
+        let offset = content.map_or(Vector::new(0.0, 0.0), |content| {
+            state.offset(layout.bounds(), content.bounds())
+        });
+        let viewport = layout.bounds().size();
+        let content = content.map_or(Size::ZERO, |content| content.bounds().size());
+
+        // compared in place, as this runs for every frame
+        let is_reported = reported.is_some_and(|reported| {
+            (
+                reported.version,
+                reported.offset,
+                reported.viewport,
+                reported.content,
+            ) == (version, offset, viewport, content)
+                && reported.tiles.iter().copied().eq(tiles())
+        });
+
+        (!is_reported).then(|| Geometry {
+            version,
+            offset,
+            viewport,
+            content,
+            tiles: tiles().collect(),
+        })
+    }
+}
+
+impl<'a> Widget<Message, Renderer> for Measure<'a> {
+    fn tag(&self) -> tree::Tag {
+        // the geometry last reported
+        tree::Tag::of::<Option<Geometry>>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(None::<Geometry>)
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        vec![Tree::new(&self.content)]
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        tree.diff_children(std::slice::from_ref(&self.content));
+    }
+
+    fn width(&self) -> Length {
+        self.content.as_widget().width()
+    }
+
+    fn height(&self) -> Length {
+        self.content.as_widget().height()
+    }
+
+    fn layout(&self, renderer: &Renderer, limits: &Limits) -> Node {
+        self.content.as_widget().layout(renderer, limits)
+    }
+
+    fn operate(
+        &self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        operation: &mut dyn Operation<Message>,
+    ) {
+        self.content
+            .as_widget()
+            .operate(&mut tree.children[0], layout, renderer, operation);
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+    ) -> event::Status {
+        // measured once per frame, as it is about to be drawn
+        if let Event::Window(window::Event::RedrawRequested(_)) = event {
+            let measured = self.measure(
+                tree.state.downcast_ref::<Option<Geometry>>().as_ref(),
+                tree.children[0]
+                    .state
+                    .downcast_ref::<iced_native::widget::scrollable::State>(),
+                layout,
+            );
+            if let Some(geometry) = measured {
+                *tree.state.downcast_mut::<Option<Geometry>>() = Some(geometry.clone());
+                shell.publish(Message::Measured(geometry));
+            }
+        }
+
+        self.content.as_widget_mut().on_event(
+            &mut tree.children[0],
+            event,
+            layout,
+            cursor_position,
+            renderer,
+            clipboard,
+            shell,
+        )
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        self.content.as_widget().mouse_interaction(
+            &tree.children[0],
+            layout,
+            cursor_position,
+            viewport,
+            renderer,
+        )
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &iced::Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+    ) {
+        self.content.as_widget().draw(
+            &tree.children[0],
+            renderer,
+            theme,
+            style,
+            layout,
+            cursor_position,
+            viewport,
+        );
+    }
+}
+
+impl<'a> From<Measure<'a>> for Element<'a, Message> {
+    fn from(measure: Measure<'a>) -> Self {
+        Element::new(measure)
     }
 }
 
 pub struct ImageTile {
-    _uid: u32,
+    uid: u32,
     path: PathBuf,
     handle: Option<image::Handle>,
+    file_size: Option<u64>,
+    caption: String,
+    rating: i64,
 }
 
 impl ImageTile {
-    pub fn new(uid: u32, path: &str) -> Self {
+    pub fn new(uid: u32, path: impl Into<PathBuf>) -> Self {
         Self {
-            _uid: uid,
+            uid,
             path: path.into(),
             handle: None,
+            file_size: None,
+            caption: String::new(),
+            rating: 0,
         }
     }
 
     pub fn load(&mut self) {
-        self.handle = Some(image::Handle::from_path(self.path.clone()))
+        self.handle = Some(image::Handle::from_path(self.path.clone()));
+        self.file_size = std::fs::metadata(&self.path).ok().map(|meta| meta.len());
+    }
+
+    /// Returns the key of the layout of the tile, which changes once its
+    /// image is loaded.
+    fn layout_key(&self) -> u64 {
+        (u64::from(self.uid) << 1) | u64::from(self.handle.is_some())
+    }
+
+    fn file_name(&self) -> String {
+        self.path
+            .file_name()
+            .map_or_else(String::new, |name| name.to_string_lossy().into_owned())
+    }
+
+    fn extension(&self) -> String {
+        self.path
+            .extension()
+            .map_or_else(String::new, |ext| ext.to_string_lossy().to_uppercase())
     }
 
     pub fn view(&self, size: Length) -> Element<Message> {
@@ -7,6 +7,9 @@ use iced_native::row;
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 
+use crate::data_grid::{self, CellEdited, DataGrid, Value};
+use crate::grid::Track;
+
 static INPUT_ID: Lazy<text_input::Id> = Lazy::new(text_input::Id::unique);
 
 #[derive(Debug)]
@@ -96,6 +99,29 @@ impl Application for Todos {
                             Command::none()
                         }
                     }
+                    Self::Message::TaskEdited(CellEdited {
+                        row,
+                        value: Value::Bool(completed),
+                        ..
+                    }) => {
+                        if let Some(task) = state.tasks.get_mut(row) {
+                            task.update(TaskMessage::Completed(completed));
+                        }
+                        Command::none()
+                    }
+                    Self::Message::TaskEdited(CellEdited {
+                        row,
+                        value: Value::Text(description),
+                        ..
+                    }) => {
+                        // a task keeps its description if it is cleared
+                        if let Some(task) = state.tasks.get_mut(row) {
+                            if !description.is_empty() {
+                                task.update(TaskMessage::DescriptionEdited(description));
+                            }
+                        }
+                        Command::none()
+                    }
                     Self::Message::Saved(_) => {
                         state.saving = false;
                         saved = true;
@@ -160,19 +186,32 @@ impl Application for Todos {
                 let controls = view_controls(tasks, *filter);
                 let filtered_tasks = tasks.iter().filter(|task| filter.matches(task));
                 let tasks: Element<_> = if filtered_tasks.count() > 0 {
-                    column(
-                        tasks
-                            .iter()
-                            .enumerate()
-                            .filter(|(_, task)| filter.matches(task))
-                            .map(|(i, task)| {
-                                task.view(i)
-                                    .map(move |message| Self::Message::TaskMessage(i, message))
+                    let filter = *filter;
+                    let columns = vec![
+                        data_grid::Column::new("Task", |i, task: &Task| {
+                            task.view(i)
+                                .map(move |message| Self::Message::TaskMessage(i, message))
+                        })
+                        .width(Track::Fill)
+                        .sort_by_key(|task: &Task| task.description.to_lowercase())
+                        .editable(|task: &Task| Value::Text(task.description.clone())),
+                        data_grid::Column::new("Status", |_, task: &Task| {
+                            text(if task.completed {
+                                "Completed"
+                            } else {
+                                "Active"
                             })
-                            .collect(),
-                    )
-                    .spacing(10)
-                    .into()
+                            .into()
+                        })
+                        .sort_by_key(|task: &Task| task.completed)
+                        .editable(|task: &Task| Value::Bool(task.completed)),
+                    ];
+
+                    DataGrid::new(columns, tasks)
+                        .filter(move |task| filter.matches(task))
+                        .spacing(10)
+                        .on_cell_edited(Self::Message::TaskEdited)
+                        .into()
                 } else {
                     empty_message(match filter {
                         Filter::All => "You have not created a task yet...",
@@ -229,6 +268,7 @@ pub enum Message {
     CreateTask,
     FilterChanged(Filter),
     TaskMessage(usize, TaskMessage),
+    TaskEdited(CellEdited),
     TabPressed { shift: bool },
 }
 